@@ -1,16 +1,181 @@
 use darling::FromDeriveInput;
+use surreal_devl::config::SurrealDeriveConfig;
+use surreal_devl::naming_convention::{camel_to_snake_case, snake_case_to_camel};
+use syn::{Expr, Lit, Meta};
 
-#[derive(FromDeriveInput)]
+use crate::config::MacroConfig;
+
+#[derive(FromDeriveInput, Default)]
 #[darling(attributes(surreal_derive))]
 #[warn(dead_code)]
 pub struct SurrealDeriveAttribute {
-    pub tag: Option<String>
+    pub tag: Option<String>,
+    pub table: Option<String>,
+    pub id: Option<String>,
+    #[darling(default)]
+    pub pregenerate_id: bool,
+    pub version: Option<u32>,
+    pub upgrade: Option<syn::Path>,
+    pub camel_case: Option<bool>,
+}
+
+impl SurrealDeriveAttribute {
+    /// Table of the record, given by `#[surreal_derive(table = "...")]` or the snake_case name of the type.
+    pub fn table_name(&self, ident: &syn::Ident) -> String {
+        self.table.clone().unwrap_or_else(|| camel_to_snake_case(ident.to_string().as_str()))
+    }
+
+    /// The Cargo.toml settings, the naming convention of `#[surreal_derive(camel_case = true)]` takes precedence.
+    pub fn config(&self) -> SurrealDeriveConfig {
+        let mut config = MacroConfig::get().derive;
+        if let Some(camel_case) = self.camel_case {
+            config.use_camel_case = camel_case;
+        }
+
+        config
+    }
+}
+
+// Comma separated metas inside an attribute, eg: #[surreal_field(name = "x", default)]
+pub struct MetaList {
+    pub items: syn::punctuated::Punctuated<syn::Meta, syn::Token![,]>
+}
+
+impl syn::parse::Parse for MetaList {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(MetaList {
+            items: syn::punctuated::Punctuated::parse_terminated(input)?
+        })
+    }
+}
+
+// Define a struct to hold field attribute configuration
+#[derive(Default)]
+pub struct FieldAttributes {
+    pub db_name: Option<String>,
+    pub db_type: Option<String>,
+    pub skip_serializing: bool,
+    pub skip_deserializing: bool,
+    pub default: bool,
+    pub id: bool,
+    pub record_id: bool,
+    pub index: bool,
+    pub unique: bool,
+    pub nested: bool,
+    pub sensitive: bool,
 }
 
-impl Default for SurrealDeriveAttribute {
-    fn default() -> Self {
-        Self {
-            tag: None
+impl FieldAttributes {
+    /// Name of the field inside the database, either given by `#[surreal_field(name = "...")]`
+    /// or derived from the rust name with the configured naming convention.
+    /// A `#[surreal_field(record_id)]` field is always read from `id`.
+    pub fn db_name(&self, field_name: &syn::Ident, config: &SurrealDeriveConfig) -> String {
+        if self.record_id {
+            return "id".to_owned();
+        }
+
+        match &self.db_name {
+            Some(name) => name.clone(),
+            None => match config.use_camel_case {
+                true => snake_case_to_camel(field_name.to_string().as_str()),
+                false => camel_to_snake_case(field_name.to_string().as_str())
+            }
+        }
+    }
+}
+
+fn lit_str(expr: &Expr) -> Option<String> {
+    if let Expr::Lit(expr_lit) = expr {
+        if let Lit::Str(lit) = &expr_lit.lit {
+            return Some(lit.value());
         }
     }
+
+    None
+}
+
+// Function to extract field attributes
+pub fn extract_field_attributes(field: &syn::Field) -> FieldAttributes {
+    let mut attrs = FieldAttributes::default();
+
+    for attr in &field.attrs {
+        // Check for #[surreal_field(...)] attributes
+        if attr.path().is_ident("surreal_field") {
+            if let Meta::List(list) = &attr.meta {
+                if let Ok(meta_list) = syn::parse2::<MetaList>(list.tokens.clone()) {
+                    for item in meta_list.items {
+                        match item {
+                            // Handle name = "value" attribute
+                            Meta::NameValue(nv) if nv.path.is_ident("name") => {
+                                if let Some(name) = lit_str(&nv.value) {
+                                    attrs.db_name = Some(name);
+                                }
+                            },
+                            // Handle db_type = "value" attribute, overrides the schema type
+                            Meta::NameValue(nv) if nv.path.is_ident("db_type") => {
+                                if let Some(db_type) = lit_str(&nv.value) {
+                                    attrs.db_type = Some(db_type);
+                                }
+                            },
+                            // Handle skip_serializing flag
+                            Meta::Path(path) if path.is_ident("skip_serializing") => {
+                                attrs.skip_serializing = true;
+                            },
+                            // Handle skip_deserializing flag
+                            Meta::Path(path) if path.is_ident("skip_deserializing") => {
+                                attrs.skip_deserializing = true;
+                            },
+                            // Handle default flag
+                            Meta::Path(path) if path.is_ident("default") => {
+                                attrs.default = true;
+                            },
+                            // Handle id flag, the field is part of the record id
+                            Meta::Path(path) if path.is_ident("id") => {
+                                attrs.id = true;
+                            },
+                            // Handle record_id flag, the field receives the `id` of the record
+                            Meta::Path(path) if path.is_ident("record_id") => {
+                                attrs.record_id = true;
+                            },
+                            // Handle index flag
+                            Meta::Path(path) if path.is_ident("index") => {
+                                attrs.index = true;
+                            },
+                            // Handle unique flag, a unique field is always indexed
+                            Meta::Path(path) if path.is_ident("unique") => {
+                                attrs.index = true;
+                                attrs.unique = true;
+                            },
+                            // Handle nested flag, the field type is derived and its paths are reachable
+                            Meta::Path(path) if path.is_ident("nested") => {
+                                attrs.nested = true;
+                            },
+                            // Handle sensitive flag, the value is masked in the logs
+                            Meta::Path(path) if path.is_ident("sensitive") => {
+                                attrs.sensitive = true;
+                            },
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        // Also check for the existing #[surreal(default)] attribute for backward compatibility
+        else if attr.path().is_ident("surreal") {
+            if let Meta::List(list) = &attr.meta {
+                if let Ok(meta_list) = syn::parse2::<MetaList>(list.tokens.clone()) {
+                    for item in meta_list.items {
+                        if let Meta::Path(path) = item {
+                            if path.is_ident("default") {
+                                attrs.default = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    attrs
 }
@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use cargo_metadata::MetadataCommand;
+use proc_macro2::TokenStream;
+use quote::quote;
+use surreal_devl::config::SurrealDeriveConfig;
+use syn::parse::{Parse, ParseStream};
+use syn::Token;
+
+// The settings of this crate, `surreal_<name>` in the metadata and `SURREAL_DERIVE_<NAME>` in the environment
+const SETTINGS: [&str; 6] = ["use_camel_case", "enable_log", "enable_compile_log", "namespace", "info_log_macro", "compile_dump_dir"];
+
+fn env_var(name: &str) -> String {
+    format!("SURREAL_DERIVE_{}", name.to_uppercase())
+}
+
+// The `[workspace.metadata]` overridden by the `[package.metadata]` of the crate, with the manifests they come from
+#[derive(Clone, Debug, Default)]
+struct Manifests {
+    settings: serde_json::Map<String, serde_json::Value>,
+    paths: Vec<PathBuf>,
+}
+
+impl Manifests {
+    fn read(root: &str) -> Self {
+        let manifest = Path::new(root).join("Cargo.toml");
+        let Ok(metadata) = MetadataCommand::new().manifest_path(&manifest).no_deps().exec() else {
+            return Self::default();
+        };
+        // The package being compiled, not the root package of the workspace
+        let package_metadata = metadata.packages.iter()
+            .find(|it| it.manifest_path.as_std_path() == manifest)
+            .map(|it| it.metadata.clone())
+            .unwrap_or_default();
+
+        let mut settings = serde_json::Map::new();
+        for metadata in [&metadata.workspace_metadata, &package_metadata] {
+            if let Some(metadata) = metadata.as_object() {
+                settings.extend(metadata.clone());
+            }
+        }
+
+        let mut paths = vec![metadata.workspace_root.join("Cargo.toml").into_std_path_buf()];
+        if !paths.contains(&manifest) {
+            paths.push(manifest);
+        }
+
+        Self { settings, paths }
+    }
+}
+
+/// Settings of the macros, from the `[workspace.metadata]`, the `[package.metadata]` then the `SURREAL_DERIVE_*`
+/// environment variables, each source takes precedence over the previous one
+#[derive(Clone, Debug)]
+pub struct MacroConfig {
+    pub derive: SurrealDeriveConfig,
+    // `surreal_compile_dump_dir`, relative to the root of the crate, receives the generated code of every call site
+    pub compile_dump_dir: Option<PathBuf>,
+    manifests: Vec<PathBuf>,
+}
+
+impl MacroConfig {
+    // The manifests are read once per crate, the macros of a crate are expanded by the same process
+    pub fn get() -> Self {
+        static MANIFESTS: Mutex<BTreeMap<String, Manifests>> = Mutex::new(BTreeMap::new());
+
+        let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let manifests = MANIFESTS.lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry(root.clone())
+            .or_insert_with(|| Manifests::read(&root))
+            .clone();
+
+        Self::resolve(&root, manifests, |name| std::env::var(name).ok())
+    }
+
+    // The settings of the manifests overridden by the environment, read through `env`
+    fn resolve(root: &str, manifests: Manifests, env: impl Fn(&str) -> Option<String>) -> Self {
+        let setting = |name: &str| match env(&env_var(name)) {
+            Some(value) => Some(match value.as_str() {
+                "true" | "1" => serde_json::Value::Bool(true),
+                "false" | "0" => serde_json::Value::Bool(false),
+                _ => serde_json::Value::String(value),
+            }),
+            None => manifests.settings.get(&format!("surreal_{}", name)).cloned(),
+        };
+
+        let mut derive = SurrealDeriveConfig::default();
+        if let Some(v) = setting("use_camel_case").and_then(|it| it.as_bool()) {
+            derive.use_camel_case = v;
+        }
+        if let Some(v) = setting("enable_log").and_then(|it| it.as_bool()) {
+            derive.enable_log = v;
+        }
+        if let Some(v) = setting("enable_compile_log").and_then(|it| it.as_bool()) {
+            derive.enable_compile_log = v;
+        }
+        if let Some(serde_json::Value::String(v)) = setting("namespace") {
+            derive.namespace = v;
+        }
+        if let Some(serde_json::Value::String(v)) = setting("info_log_macro") {
+            derive.info_log_macro = v;
+        }
+
+        Self {
+            derive,
+            compile_dump_dir: match setting("compile_dump_dir") {
+                Some(serde_json::Value::String(v)) => Some(Path::new(root).join(v)),
+                _ => None,
+            },
+            manifests: manifests.paths,
+        }
+    }
+
+    // Items reading the manifests and the environment variables, so that the compiler rebuilds the crate when they change
+    fn tracked(&self) -> TokenStream {
+        let manifests = self.manifests.iter().map(|it| it.to_string_lossy().into_owned());
+        let variables = SETTINGS.map(env_var);
+
+        quote! {
+            #(const _: &str = include_str!(#manifests);)*
+            #(const _: Option<&str> = option_env!(#variables);)*
+        }
+    }
+}
+
+/// The output of a macro expanded as an expression, eg: `surreal_quote!`, rebuilt when a setting changes
+pub fn tracked_expr(output: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let tracked = MacroConfig::get().tracked();
+    let output = TokenStream::from(output);
+
+    quote! {{
+        #tracked
+        #output
+    }}
+    .into()
+}
+
+/// The output of a macro expanded as items, eg: `#[derive(SurrealDerive)]`, rebuilt when a setting changes
+pub fn tracked_items(output: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let tracked = MacroConfig::get().tracked();
+    let output = TokenStream::from(output);
+
+    quote! {
+        #tracked
+        #output
+    }
+    .into()
+}
+
+/// Settings given where the macro is called, eg: `surreal_quote!(log = false, namespace = "billing", "...")`,
+/// they take precedence over the Cargo.toml metadata and the environment
+#[derive(Clone, Debug, Default)]
+pub struct ConfigOverrides {
+    pub enable_log: Option<bool>,
+    pub enable_compile_log: Option<bool>,
+    pub namespace: Option<String>,
+    pub info_log_macro: Option<String>,
+}
+
+impl ConfigOverrides {
+    pub fn apply(&self, mut config: SurrealDeriveConfig) -> SurrealDeriveConfig {
+        if let Some(v) = self.enable_log {
+            config.enable_log = v;
+        }
+        if let Some(v) = self.enable_compile_log {
+            config.enable_compile_log = v;
+        }
+        if let Some(v) = &self.namespace {
+            config.namespace = v.clone();
+        }
+        if let Some(v) = &self.info_log_macro {
+            config.info_log_macro = v.clone();
+        }
+
+        config
+    }
+}
+
+// `name = literal,`, unlike a typed entry of a transaction, eg: `i64 = "RETURN 1";`
+fn is_option(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<syn::Ident>().is_ok() && fork.parse::<Token![=]>().is_ok() && fork.parse::<syn::Lit>().is_ok() && fork.peek(Token![,])
+}
+
+// The leading `name = value,` options, nothing is consumed when there is none
+impl Parse for ConfigOverrides {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut overrides = Self::default();
+
+        while is_option(input) {
+            let option = input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            match option.to_string().as_str() {
+                "log" => overrides.enable_log = Some(input.parse::<syn::LitBool>()?.value),
+                "compile_log" => overrides.enable_compile_log = Some(input.parse::<syn::LitBool>()?.value),
+                "namespace" => overrides.namespace = Some(input.parse::<syn::LitStr>()?.value()),
+                "info_log_macro" => overrides.info_log_macro = Some(input.parse::<syn::LitStr>()?.value()),
+                _ => return Err(syn::Error::new(
+                    option.span(),
+                    format!("Unknown option `{}`, expected `log`, `compile_log`, `namespace` or `info_log_macro`", option),
+                )),
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(overrides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Manifests, MacroConfig};
+
+    // A workspace with one member, each manifest sets a part of the settings
+    fn workspace(dir: &Path) -> String {
+        let member = dir.join("member");
+        std::fs::create_dir_all(member.join("src")).unwrap();
+        std::fs::write(member.join("src/lib.rs"), "").unwrap();
+        std::fs::write(dir.join("Cargo.toml"), r#"
+            [workspace]
+            members = ["member"]
+
+            [workspace.metadata]
+            surreal_use_camel_case = true
+            surreal_enable_log = true
+            surreal_namespace = "workspace"
+        "#).unwrap();
+        std::fs::write(member.join("Cargo.toml"), r#"
+            [package]
+            name = "member"
+            version = "0.1.0"
+            edition = "2021"
+
+            [package.metadata]
+            surreal_enable_log = false
+            surreal_namespace = "package"
+            surreal_compile_dump_dir = "target/expand"
+        "#).unwrap();
+
+        member.canonicalize().unwrap().to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_precedence() {
+        let dir = std::env::temp_dir().join(format!("surreal-derive-config-{}", std::process::id()));
+        let root = workspace(&dir);
+        let manifests = Manifests::read(&root);
+        assert_eq!(manifests.paths.len(), 2);
+
+        // The workspace settings are inherited, the package settings override them
+        let config = MacroConfig::resolve(&root, manifests.clone(), |_| None);
+        assert!(config.derive.use_camel_case);
+        assert!(!config.derive.enable_log);
+        assert_eq!(config.derive.namespace, "package");
+        assert_eq!(config.compile_dump_dir, Some(Path::new(&root).join("target/expand")));
+
+        // The environment overrides both
+        let config = MacroConfig::resolve(&root, manifests, |name| match name {
+            "SURREAL_DERIVE_USE_CAMEL_CASE" => Some("false".to_owned()),
+            "SURREAL_DERIVE_ENABLE_LOG" => Some("1".to_owned()),
+            "SURREAL_DERIVE_NAMESPACE" => Some("env".to_owned()),
+            _ => None,
+        });
+        assert!(!config.derive.use_camel_case);
+        assert!(config.derive.enable_log);
+        assert_eq!(config.derive.namespace, "env");
+        assert_eq!(config.compile_dump_dir, Some(Path::new(&root).join("target/expand")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,29 +1,101 @@
 extern crate proc_macro;
 mod attributes;
+mod config;
 mod surreal_derive;
+mod surreal_dump;
+mod surreal_fields;
+mod surreal_id;
+mod surreal_placeholder;
+mod surreal_queries;
+mod surreal_query;
 mod surreal_quote;
+mod surreal_schema;
+mod surreal_select;
+mod surreal_sensitive;
 use attributes::SurrealDeriveAttribute;
 use darling::FromDeriveInput;
-use syn::{parse_macro_input, Data, LitStr};
+use surreal_placeholder::Binding;
+use syn::{parse_macro_input, Data};
 
+/// The statement of a template, eg: `surreal_quote!("CREATE #record(&user)")`. Leading options take precedence
+/// over the Cargo.toml metadata and the environment for this call, eg: `surreal_quote!(log = false, namespace = "billing", "SELECT ...")`.
 #[proc_macro]
 pub fn surreal_quote(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = parse_macro_input!(input as LitStr).value();
-    surreal_quote::surreal_quote(input)
+    let input = parse_macro_input!(input as surreal_quote::QuoteInput);
+    config::tracked_expr(surreal_dump::dump_call_site(surreal_quote::surreal_quote(input, Binding::Inline)))
+}
+
+/// Same template as `surreal_quote!`, the values are bound as parameters instead of being rendered.
+/// Returns a `SurrealFragment` holding the statement and its parameters, eg: `age > $p0` with `{ p0: 18 }`,
+/// which is interpolated into other templates by `#frag(...)`.
+#[proc_macro]
+pub fn surreal_fragment(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as surreal_quote::QuoteInput);
+    config::tracked_expr(surreal_dump::dump_call_site(surreal_quote::surreal_quote(input, Binding::Params)))
+}
+
+/// Same as `surreal_fragment!`, the `$name` parameters of the template are also bound from the local variables
+/// of the same name, eg: `surreal_params!("age > $min_age")` gives `age > $min_age` with `{ min_age: 18 }`.
+/// The parameters of SurrealDB such as `$this`, `$parent` or `$auth` and the ones declared by `LET`, `FOR` or `DEFINE PARAM`
+/// are left as is, `$$name` writes `$name` without capturing it.
+#[proc_macro]
+pub fn surreal_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as surreal_quote::QuoteInput);
+    config::tracked_expr(surreal_dump::dump_call_site(surreal_quote::surreal_quote(input, Binding::Capture)))
+}
+
+/// Same as `surreal_quote!` with the type of the result, eg: `surreal_query_as!(Vec<User>, "SELECT * FROM #table(User)")`.
+/// Returns a `TypedQuery`, `.execute(&db)` runs the query and deserializes the first statement, or the one chosen by `.statement(index)`.
+#[proc_macro]
+pub fn surreal_query_as(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as surreal_query::TypedQueryInput);
+    config::tracked_expr(surreal_dump::dump_call_site(surreal_query::surreal_query_as(input)))
+}
+
+/// Statements run in a single transaction, each statement may declare the type of its result, eg:
+/// `surreal_transaction! { Option<User> = "CREATE ONLY #record(&user)"; assert "count(SELECT * FROM user) > 0", "No user"; }`.
+/// `.execute(&db)` returns the tuple of the typed results, a failed `assert` aborts the transaction with its message.
+/// The entries may follow the leading options of `surreal_quote!`, eg: `surreal_transaction! { log = false, ... }`.
+#[proc_macro]
+pub fn surreal_transaction(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as surreal_query::TransactionInput);
+    config::tracked_expr(surreal_dump::dump_call_site(surreal_query::surreal_transaction(input)))
+}
+
+/// Same as `surreal_quote!` with the template read from a file, relative to the root of the crate,
+/// eg: `surreal_quote_file!("queries/report.surql")`. Editing the file triggers a rebuild.
+#[proc_macro]
+pub fn surreal_quote_file(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as surreal_quote::QuoteInput);
+    config::tracked_expr(surreal_dump::dump_call_site(surreal_quote::surreal_quote_file(input, Binding::Inline)))
+}
+
+/// One function per `-- name: find_users(min_age: i64) -> Vec<User>` section of a file, relative to the root of the crate,
+/// eg: `surreal_queries!("queries/users.surql")`. Each function takes the arguments of its header and returns the statement,
+/// or the fragment with `binding = params`. A declared result type also generates `fetch_find_users(&db, ...)`.
+/// The path may follow the leading options of `surreal_quote!`, eg: `surreal_queries!(log = false, "queries/users.surql")`.
+#[proc_macro]
+pub fn surreal_queries(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as surreal_queries::QueriesInput);
+    config::tracked_items(surreal_dump::dump_call_site(surreal_queries::surreal_queries(input)))
 }
 
 #[proc_macro_derive(SurrealDerive, attributes(surreal_derive, surreal, surreal_field))]
 pub fn surreal_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let usage_input = input.clone();
     let derive_input = syn::parse_macro_input!(input as syn::DeriveInput);
-    let attributes = SurrealDeriveAttribute::from_derive_input(&derive_input).unwrap_or_default();
+    let attributes = match SurrealDeriveAttribute::from_derive_input(&derive_input) {
+        Ok(attributes) => attributes,
+        Err(err) => return err.write_errors().into(),
+    };
 
+    let ident = &derive_input.ident;
     if let Data::Enum(_) = derive_input.data {
         let ast: syn::ItemEnum = syn::parse_macro_input!(usage_input as syn::ItemEnum);
-        surreal_derive::surreal_derive_process_enum(ast, attributes)
+        config::tracked_items(surreal_dump::dump_derive(ident, surreal_derive::surreal_derive_process_enum(ast, attributes)))
     } else if let Data::Struct(_) = derive_input.data {
         let ast: syn::ItemStruct = syn::parse_macro_input!(usage_input as syn::ItemStruct);
-        surreal_derive::surreal_derive_process_struct(ast, attributes)
+        config::tracked_items(surreal_dump::dump_derive(ident, surreal_derive::surreal_derive_process_struct(ast, attributes)))
     } else {
         panic!("#[derive(SurrealDerive)] only works for struct and enum, other types you should implement SurrealSerializer and SurrealDeserializer manually");
     }
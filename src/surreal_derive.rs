@@ -1,94 +1,18 @@
 use quote::{quote, format_ident};
-use surreal_devl::config::SurrealDeriveConfig;
 use surreal_devl::naming_convention::{camel_to_snake_case, snake_case_to_camel};
-use syn::{Expr, Lit, Meta};
 
-use crate::attributes::SurrealDeriveAttribute;
-
-// Add this struct at the top of your file
-struct MetaList {
-    items: syn::punctuated::Punctuated<syn::Meta, syn::Token![,]>
-}
-
-impl syn::parse::Parse for MetaList {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        Ok(MetaList {
-            items: syn::punctuated::Punctuated::parse_terminated(input)?
-        })
-    }
-}
-
-// Define a struct to hold field attribute configuration
-#[derive(Default)]
-struct FieldAttributes {
-    db_name: Option<String>,
-    skip_serializing: bool,
-    skip_deserializing: bool,
-    default: bool,
-}
-
-// Function to extract field attributes
-fn extract_field_attributes(field: &syn::Field) -> FieldAttributes {
-    let mut attrs = FieldAttributes::default();
-    
-    for attr in &field.attrs {
-        // Check for #[surreal_field(...)] attributes
-        if attr.path().is_ident("surreal_field") {
-            if let Meta::List(list) = &attr.meta {
-                if let Ok(meta_list) = syn::parse2::<MetaList>(list.tokens.clone()) {
-                    for item in meta_list.items {
-                        match item {
-                            // Handle name = "value" attribute
-                            Meta::NameValue(nv) if nv.path.is_ident("name") => {
-                                if let Expr::Lit(expr_lit) = &nv.value {
-                                    if let Lit::Str(lit) = &expr_lit.lit {
-                                        attrs.db_name = Some(lit.value());
-                                    }
-                                }
-                            },
-                            // Handle skip_serializing flag
-                            Meta::Path(path) if path.is_ident("skip_serializing") => {
-                                attrs.skip_serializing = true;
-                            },
-                            // Handle skip_deserializing flag
-                            Meta::Path(path) if path.is_ident("skip_deserializing") => {
-                                attrs.skip_deserializing = true;
-                            },
-                            // Handle default flag
-                            Meta::Path(path) if path.is_ident("default") => {
-                                attrs.default = true;
-                            },
-                            _ => {}
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Also check for the existing #[surreal(default)] attribute for backward compatibility
-        else if attr.path().is_ident("surreal") {
-            if let Meta::List(list) = &attr.meta {
-                if let Ok(meta_list) = syn::parse2::<MetaList>(list.tokens.clone()) {
-                    for item in meta_list.items {
-                        if let Meta::Path(path) = item {
-                            if path.is_ident("default") {
-                                attrs.default = true;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-    
-    attrs
-}
+use crate::attributes::{extract_field_attributes, SurrealDeriveAttribute};
+use crate::surreal_fields::{surreal_fields_enum_impl, surreal_fields_struct_impl};
+use crate::surreal_id::surreal_id_impl;
+use crate::surreal_schema::surreal_schema_impl;
+use crate::surreal_select::surreal_select_impl;
+use crate::surreal_sensitive::surreal_sensitive_impl;
 
 pub fn surreal_derive_process_struct(
     ast: syn::ItemStruct,
-    _attributes: SurrealDeriveAttribute,
+    attributes: SurrealDeriveAttribute,
 ) -> proc_macro::TokenStream {
-    let config = SurrealDeriveConfig::get();
+    let config = attributes.config();
     let struct_name = &ast.ident;
 
     let from_object_field_converters = ast.fields.iter().map(|field| {
@@ -97,13 +21,7 @@ pub fn surreal_derive_process_struct(
         let field_type = &field.ty;
         
         // Use field_attrs.db_name if provided, otherwise use the default naming convention
-        let db_name: String = match &field_attrs.db_name {
-            Some(name) => name.clone(),
-            None => match config.use_camel_case {
-                true => snake_case_to_camel(field_name.to_string().as_str()),
-                false => camel_to_snake_case(field_name.to_string().as_str())
-            }
-        };
+        let db_name = field_attrs.db_name(field_name, &config);
 
         // Skip deserializing if specified
         if field_attrs.skip_deserializing {
@@ -113,7 +31,7 @@ pub fn surreal_derive_process_struct(
         } else if field_attrs.default {
             // When the field has default attribute, use default if not present
             quote! {
-                #field_name: match value_object.get(#db_name) {
+                #field_name: match surreal_field(value_object, #db_name) {
                     Some(val) => <#field_type as surreal_devl::proxy::default::SurrealDeserializer>::from_option(Some(val)).map_err(|it| surreal_devl::surreal_qr::SurrealResponseError::ParsingFieldFailed(#db_name.to_string(), Box::new(it)))?,
                     None => <#field_type as Default>::default(),
                 },
@@ -121,7 +39,7 @@ pub fn surreal_derive_process_struct(
         } else {
             // Normal case - no default attribute
             quote! {
-                #field_name: <#field_type as surreal_devl::proxy::default::SurrealDeserializer>::from_option(value_object.get(#db_name)).map_err(|it| surreal_devl::surreal_qr::SurrealResponseError::ParsingFieldFailed(#db_name.to_string(), Box::new(it)))?,
+                #field_name: <#field_type as surreal_devl::proxy::default::SurrealDeserializer>::from_option(surreal_field(value_object, #db_name)).map_err(|it| surreal_devl::surreal_qr::SurrealResponseError::ParsingFieldFailed(#db_name.to_string(), Box::new(it)))?,
             }
         }
     });
@@ -131,17 +49,11 @@ pub fn surreal_derive_process_struct(
         let field_name = field.ident.as_ref().expect("Failed to process variable name, the ident could not be empty");
         let field_type = &field.ty;
         
-        // Skip serializing if specified
-        if field_attrs.skip_serializing {
+        // Skip serializing if specified, the record id is never part of the content
+        if field_attrs.skip_serializing || field_attrs.record_id {
             quote! {}
         } else {
-            let db_name: String = match &field_attrs.db_name {
-                Some(name) => name.clone(),
-                None => match config.use_camel_case {
-                    true => snake_case_to_camel(field_name.to_string().as_str()),
-                    false => camel_to_snake_case(field_name.to_string().as_str())
-                }
-            };
+            let db_name = field_attrs.db_name(field_name, &config);
 
             quote! {
                 map.insert(#db_name.to_owned(), <#field_type as surreal_devl::proxy::default::SurrealSerializer>::serialize(value.#field_name.clone()));
@@ -149,11 +61,49 @@ pub fn surreal_derive_process_struct(
         }
     });
 
+    // Fields read from the database, a projection only selects these
+    let projection_fields = ast.fields.iter().filter_map(|field| {
+        let field_attrs = extract_field_attributes(field);
+        let field_name = field.ident.as_ref().expect("Failed to process variable name, the ident could not be empty");
+
+        (!field_attrs.skip_deserializing).then(|| field_attrs.db_name(field_name, &config))
+    });
+    let table = attributes.table_name(struct_name);
+
+    let select_name = format_ident!("{}Select", struct_name);
+    let projection = quote! {
+        impl surreal_devl::surreal_table::SurrealTable for #struct_name {
+            type Select = #select_name;
+
+            const TABLE: &'static str = #table;
+
+            const FIELDS: &'static [&'static str] = &[#(#projection_fields),*];
+        }
+    };
+
     let from_object = {
         quote::quote! {
             impl TryFrom<&surrealdb::sql::Object> for #struct_name {
                 type Error = surreal_devl::surreal_qr::SurrealResponseError;
                 fn try_from(mut value_object: &surrealdb::sql::Object) -> Result<Self, Self::Error> {
+                    // A dotted name such as `address.city` reads a nested field, eg: from a projection
+                    fn surreal_field<'a>(object: &'a surrealdb::sql::Object, name: &str) -> Option<&'a surrealdb::sql::Value> {
+                        if let Some(value) = object.get(name) {
+                            return Some(value);
+                        }
+
+                        let mut path = name.split('.');
+                        let mut value = object.get(path.next()?)?;
+                        for key in path {
+                            value = match value {
+                                surrealdb::sql::Value::Object(object) => object.get(key)?,
+                                _ => return None,
+                            };
+                        }
+
+                        Some(value)
+                    }
+
                     return Ok(Self {
                         #(#from_object_field_converters)*
                     })
@@ -162,12 +112,61 @@ pub fn surreal_derive_process_struct(
         }
     };
 
+    // Versioned records carry their version inside the `_v` field
+    let into_object_version = match attributes.version {
+        Some(version) => quote! {
+            map.insert("_v".to_owned(), surrealdb::sql::Value::from(#version));
+        },
+        None => quote! {},
+    };
+
+    // Older records go through the upgrade steps, one call per version, before being converted.
+    // A version which is negative or newer than the one of the type is never read
+    let object_version = |version: u32| quote! {
+        let object_version = match object.get("_v") {
+            Some(surrealdb::sql::Value::Number(object_version)) => u32::try_from(object_version.as_int()).ok()
+                .filter(|object_version| *object_version <= #version)
+                .ok_or_else(|| surreal_devl::surreal_qr::SurrealResponseError::OutOfRange(format!("_v: {} is not a version between 0 and {}", object_version, #version)))?,
+            _ => 0,
+        };
+    };
+    let from_object_upgrade = match (attributes.version, &attributes.upgrade) {
+        (Some(version), None) => {
+            let object_version = object_version(version);
+            quote! {
+                #object_version
+                let _ = object_version;
+            }
+        }
+        (Some(version), Some(upgrade)) => {
+            let object_version = object_version(version);
+            quote! {
+                #object_version
+
+                if object_version < #version {
+                    let mut object = object.clone();
+                    for from_version in object_version..#version {
+                        object = #upgrade(object, from_version).map_err(|it| surreal_devl::surreal_qr::SurrealResponseError::ParsingFieldFailed(format!("_v: upgrade from version {}", from_version), Box::new(it)))?;
+                    }
+
+                    return Self::try_from(&object).map_err(|it| surreal_devl::surreal_qr::SurrealResponseError::ParsingFieldFailed(format!("_v: upgraded from version {}", object_version), Box::new(it)));
+                }
+            }
+        }
+        (None, Some(upgrade)) => {
+            let message = "#[surreal_derive(upgrade = \"...\")] requires a version, eg: #[surreal_derive(version = 2, upgrade = \"...\")]";
+            return syn::Error::new_spanned(upgrade, message).to_compile_error().into();
+        }
+        _ => quote! {},
+    };
+
     let into_object = {
         quote::quote! {
             impl From<#struct_name> for surrealdb::sql::Object {
                 fn from(mut value: #struct_name) -> Self {
                     let mut map: std::collections::BTreeMap<String, surrealdb::sql::Value> = std::collections::BTreeMap::new();
                     #(#into_object_field_converters)*
+                    #into_object_version
 
                     return Self::from(map)
                 }
@@ -175,39 +174,61 @@ pub fn surreal_derive_process_struct(
         }
     };
 
+    let schema = surreal_schema_impl(&ast, &attributes, &config);
+    let id = surreal_id_impl(&ast, &attributes);
+    let fields = surreal_fields_struct_impl(&ast, &config);
+    let select = surreal_select_impl(&ast, &config);
+    let sensitive = surreal_sensitive_impl(&ast, &config);
+
+    // The generated types stay out of the module of the type, they are reached through the traits
     let gen = quote::quote! {
-        #from_object
+        const _: () = {
+            #from_object
 
-        #into_object
+            #into_object
 
-        impl surreal_devl::proxy::default::SurrealDeserializer for #struct_name {
-            fn deserialize(value: &surrealdb::sql::Value) -> Result<Self, surreal_devl::surreal_qr::SurrealResponseError> {
-                let object = match &value {
-                    surrealdb::sql::Value::Object(ref value) => value,
-                    surrealdb::sql::Value::Array(ref value) => {
-                        if value.len() != 1 {
-                            return Err(surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnArrayWith1ItemToDeserializeToObject(format!("{:?}", value)))
-                        }
-                        else if let Some(surrealdb::sql::Value::Object(ref obj)) = value.0.first() {
-                            obj
-                        }
-                        else {
-                            return Err(surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnObject(format!("{:?}", value)))
+            #schema
+
+            #id
+
+            #projection
+
+            #fields
+
+            #select
+
+            #sensitive
+
+            impl surreal_devl::proxy::default::SurrealDeserializer for #struct_name {
+                fn deserialize(value: &surrealdb::sql::Value) -> Result<Self, surreal_devl::surreal_qr::SurrealResponseError> {
+                    let object = match &value {
+                        surrealdb::sql::Value::Object(ref value) => value,
+                        surrealdb::sql::Value::Array(ref value) => {
+                            if value.len() != 1 {
+                                return Err(surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnArrayWith1ItemToDeserializeToObject(format!("{:?}", value)))
+                            }
+                            else if let Some(surrealdb::sql::Value::Object(ref obj)) = value.0.first() {
+                                obj
+                            }
+                            else {
+                                return Err(surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnObject(format!("{:?}", value)))
+                            }
                         }
-                    }
-                    _ => return Err(surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnObject(format!("{:?}", value))),
-                };
+                        _ => return Err(surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnObject(format!("{:?}", value))),
+                    };
 
-                Ok(Self::try_from(object)?)
+                    #from_object_upgrade
+                    Self::try_from(object)
+                }
             }
-        }
 
-        impl surreal_devl::proxy::default::SurrealSerializer for #struct_name {
-            fn serialize(self) -> surrealdb::sql::Value {
-                let obj: surrealdb::sql::Object = self.into();
-                surrealdb::sql::Value::Object(obj)
+            impl surreal_devl::proxy::default::SurrealSerializer for #struct_name {
+                fn serialize(self) -> surrealdb::sql::Value {
+                    let obj: surrealdb::sql::Object = self.into();
+                    surrealdb::sql::Value::Object(obj)
+                }
             }
-        }
+        };
     };
 
     gen.into()
@@ -217,11 +238,12 @@ pub fn surreal_derive_process_enum(
     ast: syn::ItemEnum,
     attributes: SurrealDeriveAttribute,
 ) -> proc_macro::TokenStream {
-    let config = SurrealDeriveConfig::get();
+    let config = attributes.config();
     let enum_name = &ast.ident;
+    let fields = surreal_fields_enum_impl(&ast, &attributes, &config);
 
     // Determine tag field name based on attributes
-    let tag_field = attributes.tag.unwrap_or_else(|| "".to_string());
+    let tag_field = attributes.tag.unwrap_or_default();
     if !tag_field.is_empty() && tag_field.ne("type") {
         panic!("Invalid tag field name, only \"type\" is allowed");
     }
@@ -404,55 +426,59 @@ pub fn surreal_derive_process_enum(
     });
 
     let gen = quote! {
-        impl surreal_devl::proxy::default::SurrealSerializer for #enum_name {
-            fn serialize(self) -> surrealdb::sql::Value {
-                match self {
-                    #(#serialize_match_arms)*
+        const _: () = {
+            #fields
+
+            impl surreal_devl::proxy::default::SurrealSerializer for #enum_name {
+                fn serialize(self) -> surrealdb::sql::Value {
+                    match self {
+                        #(#serialize_match_arms)*
+                    }
                 }
             }
-        }
 
-        impl surreal_devl::proxy::default::SurrealDeserializer for #enum_name {
-            fn deserialize(value: &surrealdb::sql::Value) -> Result<Self, surreal_devl::surreal_qr::SurrealResponseError> {
-                let mut fake_obj = surrealdb::sql::Object::from(std::collections::BTreeMap::<String, surrealdb::sql::Value>::new());
-                let obj = match value {
-                    surrealdb::sql::Value::Object(obj) => obj,
-                    surrealdb::sql::Value::Strand(strand) => {
-                        if #use_type_value_format {
-                            fake_obj.0.insert("type".to_string(), surrealdb::sql::Value::from(strand.0.clone()));
-                            fake_obj.0.insert("value".to_string(), surrealdb::sql::Value::from(strand.0.clone()));
-                        } else {
-                            fake_obj.0.insert(strand.0.clone(), surrealdb::sql::Value::from(strand.0.clone()));
-                        }
-                        &fake_obj
-                    },
-                    _ => return Err(surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnObject(format!("{:?}", value))),
-                };
-
-                let (variant_name, variant_value) = if #use_type_value_format {
-                    let type_value = obj.get("type")
-                        .ok_or(surreal_devl::surreal_qr::SurrealResponseError::TypeEnumMustBeString(format!("{:?}", obj)))?;
-                    let variant_value = obj.get("value")
-                        .unwrap_or(type_value);
+            impl surreal_devl::proxy::default::SurrealDeserializer for #enum_name {
+                fn deserialize(value: &surrealdb::sql::Value) -> Result<Self, surreal_devl::surreal_qr::SurrealResponseError> {
+                    let mut fake_obj = surrealdb::sql::Object::from(std::collections::BTreeMap::<String, surrealdb::sql::Value>::new());
+                    let obj = match value {
+                        surrealdb::sql::Value::Object(obj) => obj,
+                        surrealdb::sql::Value::Strand(strand) => {
+                            if #use_type_value_format {
+                                fake_obj.0.insert("type".to_string(), surrealdb::sql::Value::from(strand.0.clone()));
+                                fake_obj.0.insert("value".to_string(), surrealdb::sql::Value::from(strand.0.clone()));
+                            } else {
+                                fake_obj.0.insert(strand.0.clone(), surrealdb::sql::Value::from(strand.0.clone()));
+                            }
+                            &fake_obj
+                        },
+                        _ => return Err(surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnObject(format!("{:?}", value))),
+                    };
+
+                    let (variant_name, variant_value) = if #use_type_value_format {
+                        let type_value = obj.get("type")
+                            .ok_or(surreal_devl::surreal_qr::SurrealResponseError::TypeEnumMustBeString(format!("{:?}", obj)))?;
+                        let variant_value = obj.get("value")
+                            .unwrap_or(type_value);
                     
-                    match type_value {
-                        surrealdb::sql::Value::Strand(s) => (s.0.as_str(), variant_value),
-                        _ => return Err(surreal_devl::surreal_qr::SurrealResponseError::InvalidEnumFormat(format!("{:?}", type_value))),
-                    }
-                } else {
-                    if obj.len() != 1 {
-                        return Err(surreal_devl::surreal_qr::SurrealResponseError::InvalidEnumFormat(format!("{:?}", obj)));
-                    }
-                    let (name, value) = obj.iter().next().unwrap();
-                    (name.as_str(), value)
-                };
+                        match type_value {
+                            surrealdb::sql::Value::Strand(s) => (s.0.as_str(), variant_value),
+                            _ => return Err(surreal_devl::surreal_qr::SurrealResponseError::InvalidEnumFormat(format!("{:?}", type_value))),
+                        }
+                    } else {
+                        if obj.len() != 1 {
+                            return Err(surreal_devl::surreal_qr::SurrealResponseError::InvalidEnumFormat(format!("{:?}", obj)));
+                        }
+                        let (name, value) = obj.iter().next().unwrap();
+                        (name.as_str(), value)
+                    };
 
-                match variant_name {
-                    #(#deserialize_match_arms)*
-                    _ => Err(surreal_devl::surreal_qr::SurrealResponseError::UnknownVariant(format!("{variant_name:?}"))),
+                    match variant_name {
+                        #(#deserialize_match_arms)*
+                        _ => Err(surreal_devl::surreal_qr::SurrealResponseError::UnknownVariant(format!("{variant_name:?}"))),
+                    }
                 }
             }
-        }
+        };
     };
 
     gen.into()
@@ -0,0 +1,59 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::config::MacroConfig;
+
+// A readable rendering of the generated code. The expression of a macro call is printed as the body of a function
+pub fn pretty(tokens: &TokenStream) -> String {
+    let file = syn::parse2::<syn::File>(tokens.clone()).or_else(|_| syn::parse2::<syn::File>(quote! {
+        fn surreal_expanded() {
+            #tokens
+        }
+    }));
+
+    match file {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(_) => format!("{}\n", tokens),
+    }
+}
+
+/// Write the generated code into `surreal_compile_dump_dir`, as `<name>.rs`
+pub fn dump(name: &str, output: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let Some(dir) = MacroConfig::get().compile_dump_dir else {
+        return output;
+    };
+
+    let path = dir.join(format!("{}.rs", name));
+    let written = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, pretty(&output.clone().into())));
+    if let Err(err) = written {
+        let message = format!("Failed to write the generated code into {}: {}", path.display(), err);
+        return quote! { compile_error!(#message); }.into();
+    }
+
+    output
+}
+
+// The location of a span as a file name, eg: `src_queries.rs_12`
+fn location(span: proc_macro::Span) -> String {
+    format!("{}_{}", span.file().replace(['/', '\\'], "_"), span.line())
+}
+
+/// Write the generated code of a macro call, named after its location, eg: `src_queries.rs_12_5.rs`
+pub fn dump_call_site(output: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let span = proc_macro::Span::call_site();
+    dump(&format!("{}_{}", location(span), span.column()), output)
+}
+
+/// Write the generated code of a derive, named after the location of the type, eg: `src_models.rs_8_User.rs`.
+/// Types of the same name in other modules are dumped into their own files
+pub fn dump_derive(ident: &syn::Ident, output: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    dump(&format!("{}_{}", location(ident.span().unwrap()), ident), output)
+}
+
+/// Print the generated code of a template on stderr with `surreal_enable_compile_log`, prefixed by the namespace and the
+/// location of the macro call, eg: `[surreal-ql] src/queries.rs:12: { ... }`. Cargo shows it for the failing builds
+/// or with `cargo build -vv`, `surreal_compile_dump_dir` keeps it in a file instead
+pub fn compile_log(namespace: &str, output: &TokenStream) {
+    let span = proc_macro::Span::call_site();
+    eprintln!("[{}] {}:{}: {}", namespace, span.file(), span.line(), pretty(output).trim_end());
+}
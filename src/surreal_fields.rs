@@ -0,0 +1,236 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use surreal_devl::config::SurrealDeriveConfig;
+use surreal_devl::naming_convention::{camel_to_snake_case, snake_case_to_camel};
+use syn::{GenericArgument, PathArguments, Type};
+
+use crate::attributes::{extract_field_attributes, SurrealDeriveAttribute};
+
+// Paths type of a nested field, eg: `Option<models::Address>` gives the `Fields` of `models::Address`.
+// Arrays are walked through, `addresses.city` reads the city of every address
+fn nested_fields_type(ty: &Type) -> Option<TokenStream> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+
+    if ["Option", "Box", "Vec"].contains(&segment.ident.to_string().as_str()) {
+        let PathArguments::AngleBracketed(args) = &segment.arguments else {
+            return None;
+        };
+
+        return args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => nested_fields_type(ty),
+            _ => None,
+        });
+    }
+
+    Some(quote! { <#path as surreal_devl::surreal_table::SurrealFields>::Fields })
+}
+
+fn naming_convention(name: &str, config: &SurrealDeriveConfig) -> String {
+    match config.use_camel_case {
+        true => snake_case_to_camel(name),
+        false => camel_to_snake_case(name),
+    }
+}
+
+// The struct holding a path, which renders as the path and converts from and into an idiom
+fn fields_struct(vis: &syn::Visibility, name: &syn::Ident, doc: String) -> TokenStream {
+    quote! {
+        #[doc = #doc]
+        #[allow(dead_code)]
+        #[derive(Clone, Debug, Default, PartialEq)]
+        #vis struct #name(surrealdb::sql::Idiom);
+
+        impl From<surrealdb::sql::Idiom> for #name {
+            fn from(path: surrealdb::sql::Idiom) -> Self {
+                Self(path)
+            }
+        }
+
+        impl From<#name> for surrealdb::sql::Idiom {
+            fn from(fields: #name) -> Self {
+                fields.0
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    }
+}
+
+// The path of a single field, comparing it to a value gives a condition, eg: `age > 18`
+fn field_struct(vis: &syn::Visibility, name: &syn::Ident, doc: String) -> TokenStream {
+    let fields_struct = fields_struct(vis, name, doc);
+    let comparisons = [
+        ("eq", "Equal", "`field = value`"),
+        ("ne", "NotEqual", "`field != value`"),
+        ("gt", "MoreThan", "`field > value`"),
+        ("gte", "MoreThanOrEqual", "`field >= value`"),
+        ("lt", "LessThan", "`field < value`"),
+        ("lte", "LessThanOrEqual", "`field <= value`"),
+        ("contains", "Contain", "`field CONTAINS value`"),
+        ("inside", "Inside", "`field INSIDE value`"),
+    ].into_iter().map(|(method, operator, doc)| {
+        let method = format_ident!("{}", method);
+        let operator = format_ident!("{}", operator);
+        let doc = format!("Condition {}.", doc);
+
+        quote! {
+            #[doc = #doc]
+            #vis fn #method<V>(&self, value: V) -> surrealdb::sql::Value
+            where
+                V: surreal_devl::proxy::default::SurrealSerializer,
+            {
+                surrealdb::sql::Value::Expression(Box::new(surrealdb::sql::Expression::new(
+                    surrealdb::sql::Value::Idiom(self.0.clone()),
+                    surrealdb::sql::Operator::#operator,
+                    surreal_devl::proxy::default::SurrealSerializer::serialize(value),
+                )))
+            }
+        }
+    });
+
+    quote! {
+        #fields_struct
+
+        #[allow(dead_code)]
+        impl #name {
+            #(#comparisons)*
+        }
+    }
+}
+
+// A method returning the path of `db_name` below the current path, either as an idiom or as the paths of a nested type
+fn path_method(vis: &syn::Visibility, method: &syn::Ident, db_name: &str, field_path: &syn::Ident, nested: Option<TokenStream>) -> TokenStream {
+    let path = quote! {{
+        let mut path = self.0.to_vec();
+        path.extend(#db_name.split('.').map(surrealdb::sql::Part::from));
+        surrealdb::sql::Idiom::from(path)
+    }};
+
+    match nested {
+        Some(nested) => quote! {
+            #vis fn #method(&self) -> #nested {
+                <#nested>::from(#path)
+            }
+        },
+        None => quote! {
+            #vis fn #method(&self) -> #field_path {
+                #field_path::from(#path)
+            }
+        },
+    }
+}
+
+pub fn surreal_fields_struct_impl(ast: &syn::ItemStruct, config: &SurrealDeriveConfig) -> TokenStream {
+    let vis = &ast.vis;
+    let struct_name = &ast.ident;
+    let fields_name = format_ident!("{}Fields", struct_name);
+    let field_path = format_ident!("{}Field", struct_name);
+
+    let methods = ast.fields.iter().filter_map(|field| {
+        let field_attrs = extract_field_attributes(field);
+        if field_attrs.skip_serializing && !field_attrs.record_id {
+            return None;
+        }
+
+        let field_name = field.ident.as_ref().expect("Failed to process variable name, the ident could not be empty");
+        let nested = field_attrs.nested.then(|| nested_fields_type(&field.ty)).flatten();
+
+        Some(path_method(vis, field_name, &field_attrs.db_name(field_name, config), &field_path, nested))
+    });
+
+    let fields_struct = fields_struct(vis, &fields_name, format!("Paths to the fields of [`{}`], eg: `{}::fields().address().city()` renders `address.city`.", struct_name, struct_name));
+    let field_struct = field_struct(vis, &field_path, format!("Path to a single field of [`{}`], eg: `{}::fields().age().gt(18)`.", struct_name, struct_name));
+
+    quote! {
+        #fields_struct
+
+        #field_struct
+
+        #[allow(dead_code)]
+        impl #fields_name {
+            #(#methods)*
+        }
+
+        impl surreal_devl::surreal_table::SurrealFields for #struct_name {
+            type Fields = #fields_name;
+        }
+    }
+}
+
+pub fn surreal_fields_enum_impl(ast: &syn::ItemEnum, attributes: &SurrealDeriveAttribute, config: &SurrealDeriveConfig) -> TokenStream {
+    let vis = &ast.vis;
+    let enum_name = &ast.ident;
+    let fields_name = format_ident!("{}Fields", enum_name);
+    let field_path = format_ident!("{}Field", enum_name);
+    let tagged = attributes.tag.as_deref() == Some("type");
+
+    let mut variant_structs = vec![];
+    let mut methods = vec![];
+
+    if tagged {
+        methods.push(path_method(vis, &format_ident!("tag"), "type", &field_path, None));
+    }
+
+    for variant in ast.variants.iter() {
+        if let syn::Fields::Unit = variant.fields {
+            continue;
+        }
+
+        // Tagged variants keep their content inside `value`, the others inside the name of the variant
+        let variant_name = &variant.ident;
+        let method = format_ident!("{}", camel_to_snake_case(variant_name.to_string().as_str()));
+        let content = match tagged {
+            true => "value".to_owned(),
+            false => naming_convention(variant_name.to_string().as_str(), config),
+        };
+
+        let syn::Fields::Named(fields) = &variant.fields else {
+            methods.push(path_method(vis, &method, &content, &field_path, None));
+            continue;
+        };
+
+        let variant_fields_name = format_ident!("{}{}Fields", enum_name, variant_name);
+        let variant_methods = fields.named.iter().map(|field| {
+            let field_name = field.ident.as_ref().unwrap();
+            path_method(vis, field_name, &naming_convention(field_name.to_string().as_str(), config), &field_path, None)
+        });
+        let variant_struct = fields_struct(vis, &variant_fields_name, format!("Paths to the fields of [`{}::{}`].", enum_name, variant_name));
+
+        variant_structs.push(quote! {
+            #variant_struct
+
+            #[allow(dead_code)]
+            impl #variant_fields_name {
+                #(#variant_methods)*
+            }
+        });
+        methods.push(path_method(vis, &method, &content, &field_path, Some(quote! { #variant_fields_name })));
+    }
+
+    let fields_struct = fields_struct(vis, &fields_name, format!("Paths inside [`{}`], reachable from a `#[surreal_field(nested)]` field.", enum_name));
+    let field_struct = field_struct(vis, &field_path, format!("Path to a single value inside [`{}`].", enum_name));
+
+    quote! {
+        #fields_struct
+
+        #field_struct
+
+        #[allow(dead_code)]
+        impl #fields_name {
+            #(#methods)*
+        }
+
+        #(#variant_structs)*
+
+        impl surreal_devl::surreal_table::SurrealFields for #enum_name {
+            type Fields = #fields_name;
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::Type;
+
+use crate::attributes::{extract_field_attributes, SurrealDeriveAttribute};
+
+fn last_segment_is(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| segment.ident == name),
+        _ => false,
+    }
+}
+
+pub fn surreal_id_impl(ast: &syn::ItemStruct, attributes: &SurrealDeriveAttribute) -> TokenStream {
+    let struct_name = &ast.ident;
+    let table = quote! { <Self as surreal_devl::surreal_table::SurrealTable>::TABLE };
+
+    let id_fields: Vec<_> = ast.fields.iter().filter(|field| extract_field_attributes(field).id).map(|field| {
+        let field_name = field.ident.as_ref().expect("Failed to process variable name, the ident could not be empty");
+        let field_type = &field.ty;
+        // Uuid is serialized as a string, the id keeps it typed
+        if last_segment_is(field_type, "Uuid") {
+            quote! {
+                surrealdb::sql::Value::Uuid(surrealdb::sql::Uuid::from(self.#field_name))
+            }
+        } else {
+            quote! {
+                <#field_type as surreal_devl::proxy::default::SurrealSerializer>::serialize(self.#field_name.clone())
+            }
+        }
+    }).collect();
+
+    let record_id = ast.fields.iter().find(|field| extract_field_attributes(field).record_id);
+
+    let into_id = quote! {
+        let into_id = |value: surrealdb::sql::Value| match value {
+            surrealdb::sql::Value::Strand(id) => surrealdb::sql::Id::String(id.0),
+            surrealdb::sql::Value::Number(id) => surrealdb::sql::Id::Number(id.as_int()),
+            surrealdb::sql::Value::Uuid(id) => surrealdb::sql::Id::Uuid(id),
+            surrealdb::sql::Value::Array(id) => surrealdb::sql::Id::Array(id),
+            surrealdb::sql::Value::Object(id) => surrealdb::sql::Id::Object(id),
+            id => surrealdb::sql::Id::String(id.to_raw_string()),
+        };
+    };
+
+    // A single field becomes a string or number id, several fields become a composite array id
+    let from_id_fields = match id_fields.as_slice() {
+        [] => None,
+        [id_field] => Some(quote! { #id_field }),
+        id_fields => Some(quote! { surrealdb::sql::Value::Array(vec![#(#id_fields),*].into()) }),
+    }.map(|id_value| quote! {
+        #into_id
+        surrealdb::sql::Thing::from((#table, into_id(#id_value)))
+    });
+
+    let record_id_field = record_id.map(|field| (field, field.ident.as_ref().expect("Failed to process variable name, the ident could not be empty")));
+    let optional_record_id = record_id.is_some_and(|field| last_segment_is(&field.ty, "Option"));
+
+    // Generated ids are either left to the database, eg: `user:ulid()`, or generated once in rust into the record id field
+    let mut generate_id = quote! {};
+    let from_strategy = match attributes.id.as_ref() {
+        Some(strategy) if !["ulid", "uuid", "rand"].contains(&strategy.as_str()) => {
+            let message = format!("Invalid id strategy \"{}\", only \"ulid\", \"uuid\" and \"rand\" are allowed", strategy);
+            return syn::Error::new(struct_name.span(), message).to_compile_error();
+        }
+        Some(strategy) if attributes.pregenerate_id => {
+            if record_id.is_none() || optional_record_id {
+                let message = format!(
+                    "{} generates its id in rust, it requires a `#[surreal_field(record_id)] id: Thing` field which receives `{}::generate_id()`",
+                    struct_name, struct_name
+                );
+                return syn::Error::new(struct_name.span(), message).to_compile_error();
+            }
+
+            let generate = syn::Ident::new(strategy, proc_macro2::Span::call_site());
+            generate_id = quote! {
+                impl #struct_name {
+                    /// A new record id generated in rust, given to the record id field when the value is built.
+                    pub fn generate_id() -> surrealdb::sql::Thing {
+                        surrealdb::sql::Thing::from((#table, surrealdb::sql::Id::#generate()))
+                    }
+                }
+            };
+            None
+        }
+        Some(strategy) => Some(quote! {
+            surrealdb::syn::thing(&format!("{}:{}()", surrealdb::sql::Table::from(#table), #strategy)).expect("Failed to build the generated record id")
+        }),
+        None if attributes.pregenerate_id => {
+            let message = format!(
+                "{} generates its id in rust, `pregenerate_id` requires an id strategy, eg: `#[surreal_derive(id = \"ulid\", pregenerate_id)]`",
+                struct_name
+            );
+            return syn::Error::new(struct_name.span(), message).to_compile_error();
+        }
+        None => None,
+    };
+
+    let has_id = from_id_fields.is_some() || attributes.id.is_some();
+    let fallback = from_id_fields.or(from_strategy);
+
+    // The record id received from the database takes precedence over the id fields
+    let body = match (record_id_field, fallback) {
+        (None, None) => return quote! {},
+        (None, Some(fallback)) => fallback,
+        (Some((_, field_name)), _) if !optional_record_id => quote! { self.#field_name.clone() },
+        (Some((_, field_name)), Some(fallback)) => quote! {
+            if let Some(id) = &self.#field_name {
+                return id.clone();
+            }
+
+            #fallback
+        },
+        // A record without id has no fallback, it could never be addressed
+        (Some((field, _)), None) => {
+            let message = format!(
+                "The record id of {} is optional, add `#[surreal_field(id)]` fields or an id strategy, eg: `#[surreal_derive(id = \"rand\")]`, or make it a `Thing`",
+                struct_name
+            );
+            return syn::Error::new(field.ty.span(), message).to_compile_error();
+        }
+    };
+
+    let id_range = match has_id {
+        true => quote! {
+            impl #struct_name {
+                /// Range of record ids inside the table of this type, eg: `table:['London', NONE]..=['London', time::now()]`
+                pub fn id_range<K>(range: impl std::ops::RangeBounds<K>) -> surrealdb::sql::Thing
+                where
+                    K: surreal_devl::proxy::default::SurrealSerializer + Clone + 'static,
+                {
+                    #into_id
+                    let bound = |bound: std::ops::Bound<&K>| bound.cloned().map(|key| into_id(surreal_devl::surreal_id::id_key(key)));
+                    let range = surrealdb::sql::IdRange::try_from((bound(range.start_bound()), bound(range.end_bound()))).expect("A record id range can not be nested");
+
+                    surrealdb::sql::Thing::from((#table, surrealdb::sql::Id::Range(Box::new(range))))
+                }
+            }
+        },
+        false => quote! {},
+    };
+
+    quote! {
+        impl surreal_devl::surreal_id::SurrealId for #struct_name {
+            fn id(&self) -> surrealdb::sql::Thing {
+                #body
+            }
+        }
+
+        #id_range
+
+        #generate_id
+    }
+}
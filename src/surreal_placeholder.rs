@@ -0,0 +1,482 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Expr, Token};
+
+use crate::surreal_sensitive::redacted;
+
+// Arguments of a placeholder call, positional arguments first then options, eg: `#insert(&users, chunk = 100)`
+struct PlaceholderArgs {
+    args: Vec<Expr>,
+    options: Vec<(syn::Ident, Expr)>,
+}
+
+impl PlaceholderArgs {
+    fn parse(args: &Punctuated<Expr, Token![,]>) -> syn::Result<Self> {
+        let mut result = Self {
+            args: vec![],
+            options: vec![],
+        };
+
+        for arg in args {
+            match arg {
+                Expr::Assign(assign) => match &*assign.left {
+                    Expr::Path(path) if path.path.get_ident().is_some() => {
+                        let name = path.path.get_ident().unwrap().clone();
+                        result.options.push((name, (*assign.right).clone()));
+                    }
+                    _ => return Err(syn::Error::new_spanned(&assign.left, "Expected an option name, eg: `chunk = 100`")),
+                },
+                arg => {
+                    if !result.options.is_empty() {
+                        return Err(syn::Error::new_spanned(arg, "Options must come after the arguments"));
+                    }
+
+                    result.args.push(arg.clone());
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn option(&self, name: &str) -> Option<&Expr> {
+        self.options.iter().find(|(option, _)| option == name).map(|(_, value)| value)
+    }
+
+    // Field names of a list option, eg: `include = [name, "address.city"]`
+    fn names(&self, name: &str) -> syn::Result<Option<Vec<String>>> {
+        let Some(value) = self.option(name) else {
+            return Ok(None);
+        };
+        let Expr::Array(array) = value else {
+            return Err(syn::Error::new_spanned(value, format!("Expected a list of fields, eg: `{} = [name, \"address.city\"]`", name)));
+        };
+
+        array.elems.iter().map(|elem| match elem {
+            Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => Ok(lit.value()),
+            Expr::Path(path) if path.path.get_ident().is_some() => Ok(path.path.get_ident().unwrap().to_string()),
+            Expr::Field(_) => Ok(quote! { #elem }.to_string().replace(' ', "")),
+            elem => Err(syn::Error::new_spanned(elem, "Expected a field name")),
+        }).collect::<syn::Result<Vec<_>>>().map(Some)
+    }
+
+    fn ensure_options(&self, allowed: &[&str]) -> syn::Result<()> {
+        match self.options.iter().find(|(option, _)| !allowed.iter().any(|allowed| option == allowed)) {
+            Some((option, _)) => Err(syn::Error::new_spanned(option, format!("Unknown option `{}`", option))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// How the values of the placeholders are written into the statement
+#[derive(Clone, Copy, PartialEq)]
+pub enum Binding {
+    // The values are rendered inside the statement, eg: `age = 18`
+    Inline,
+    // The values are bound as parameters, eg: `age = $p0` with `p0: 18`
+    Params,
+    // Same as `Params`, the `$name` parameters of the template are also bound from the local variables of the same name
+    Capture,
+}
+
+// How a placeholder turns its record into a value
+#[derive(Clone, Copy)]
+enum Serialize {
+    // The value written into the statement
+    Value,
+    // The value shown in the logs, with `***` in place of the sensitive fields
+    Redacted,
+}
+
+impl Serialize {
+    fn value(self, record: &Expr) -> TokenStream {
+        match self {
+            Serialize::Value => quote! { surreal_devl::proxy::default::SurrealSerializer::serialize(Clone::clone(#record)) },
+            Serialize::Redacted => redacted(quote! { #record }),
+        }
+    }
+}
+
+/// Expand the content of a placeholder into the rust expression rendering it.
+/// Built-in placeholders such as `insert(...)` are generated inline, everything else is kept as is
+/// and resolved against `surreal_devl::surreal_statement`, eg: `record(&user)`, or the local scope.
+pub fn expand_placeholder(content: &str, span: proc_macro2::Span, binding: Binding) -> TokenStream {
+    let tokens = match syn::parse_str::<TokenStream>(content) {
+        Ok(tokens) => tokens,
+        Err(err) => return syn::Error::new(span, format!("Invalid placeholder #{}: {}", content, err)).to_compile_error(),
+    };
+    let call = match syn::parse2::<Expr>(tokens.clone()) {
+        Ok(Expr::Call(call)) => Some(call),
+        _ => None,
+    };
+    let name = call.as_ref().and_then(|call| match &*call.func {
+        Expr::Path(func) => func.path.get_ident().map(|name| name.to_string()),
+        _ => None,
+    });
+
+    let (Some(call), Some(name)) = (call, name) else {
+        return match binding {
+            Binding::Inline => tokens,
+            Binding::Params | Binding::Capture => bind(quote! { surreal_devl::proxy::default::SurrealSerializer::serialize((#tokens).clone()) }),
+        };
+    };
+
+    let expanded = PlaceholderArgs::parse(&call.args).and_then(|args| match (name.as_str(), binding) {
+        ("insert", _) => insert(&call, args, Serialize::Value),
+        ("upsert", _) => upsert(&call, args),
+        ("merge", _) => merge(&call, args, Serialize::Value),
+        ("set", _) => set(&call, args, Serialize::Value),
+        ("fields", _) => fields(&call, args),
+        ("table", _) => table(&call, args),
+        ("frag", _) => frag(&call, args, binding),
+        ("val" | "id" | "array", Binding::Params | Binding::Capture) => bind_value(&call, &name, args),
+        (_, Binding::Params | Binding::Capture) if !["record", "content", "relate"].contains(&name.as_str()) => {
+            Ok(bind(quote! { surreal_devl::proxy::default::SurrealSerializer::serialize((#tokens).clone()) }))
+        }
+        _ => Ok(tokens),
+    });
+
+    expanded.unwrap_or_else(|error| error.to_compile_error())
+}
+
+/// Expand the content of a record placeholder into the rendering shown in the logs, eg: `#val(&user)` or `#insert(&users)`,
+/// the sensitive fields of the record are `'***'`. `None` when the placeholder holds no record,
+/// or when its record cannot be evaluated a second time, eg: a call
+pub fn expand_redacted(content: &str) -> Option<TokenStream> {
+    let Ok(Expr::Call(call)) = syn::parse_str::<Expr>(content) else {
+        return None;
+    };
+    let Expr::Path(func) = &*call.func else {
+        return None;
+    };
+
+    let name = func.path.get_ident()?.to_string();
+    let args = PlaceholderArgs::parse(&call.args).ok()?;
+    let redacted = match (name.as_str(), args.args.as_slice()) {
+        // Iterated twice, only when borrowed, eg: `&users`
+        ("insert", [.., records @ Expr::Reference(_)]) if readable(records) => insert(&call, args, Serialize::Redacted),
+        ("merge", [record]) if readable(record) => merge(&call, args, Serialize::Redacted),
+        ("set", [record]) if readable(record) => set(&call, args, Serialize::Redacted),
+        ("relate", [edge]) if readable(edge) => {
+            let value = redacted(quote! { &(#edge).data });
+            Ok(quote! { surreal_devl::surreal_statement::relate_content(#edge, #value) })
+        }
+        ("val" | "content" | "record" | "upsert", [record]) if readable(record) => {
+            let value = Serialize::Redacted.value(record);
+            Ok(match name.as_str() {
+                "val" => quote! { #value.to_string() },
+                "content" => quote! { surrealdb::sql::Data::ContentExpression(#value).to_string() },
+                _ => {
+                    let prefix = if name == "upsert" { "UPSERT " } else { "" };
+                    quote! {
+                        format!("{}{} {}", #prefix, surreal_devl::surreal_id::SurrealId::id(#record), surrealdb::sql::Data::ContentExpression(#value))
+                    }
+                }
+            })
+        }
+        _ => return None,
+    };
+
+    redacted.ok()
+}
+
+// An argument evaluated a second time for the logs, eg: `&user` or `user.address`, never a call
+fn readable(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(_) | Expr::Lit(_) => true,
+        Expr::Field(field) => readable(&field.base),
+        Expr::Reference(reference) => readable(&reference.expr),
+        Expr::Paren(paren) => readable(&paren.expr),
+        Expr::Index(index) => readable(&index.expr) && readable(&index.index),
+        Expr::Unary(syn::ExprUnary { op: syn::UnOp::Deref(_), expr, .. }) => readable(expr),
+        _ => false,
+    }
+}
+
+// Bind the value as the next parameter, renders its name, eg: `$p0`
+fn bind(value: TokenStream) -> TokenStream {
+    let params = syn::Ident::new("params", proc_macro2::Span::mixed_site());
+    // The value may read a variable of the same name, eg: `#val(&name)`
+    let name = syn::Ident::new("name", proc_macro2::Span::mixed_site());
+
+    let reserved = syn::Ident::new("reserved", proc_macro2::Span::mixed_site());
+
+    quote! {{
+        let #name = surreal_devl::surreal_fragment::next_param(&#params, #reserved);
+        #params.insert(#name.clone(), #value);
+        format!("${}", #name)
+    }}
+}
+
+// #val(&x), #id(&x) and #array(&x) bound as parameters
+fn bind_value(call: &syn::ExprCall, name: &str, args: PlaceholderArgs) -> syn::Result<TokenStream> {
+    args.ensure_options(&[])?;
+    let [arg] = args.args.as_slice() else {
+        return Err(syn::Error::new_spanned(call, format!("Expected #{}(&value)", name)));
+    };
+
+    Ok(bind(match name {
+        "id" => quote! { surrealdb::sql::Value::Thing(surreal_devl::surreal_id::SurrealId::id(#arg)) },
+        "array" => quote! {
+            surrealdb::sql::Value::Array((#arg).iter().cloned().map(surreal_devl::proxy::default::SurrealSerializer::serialize).collect::<Vec<_>>().into())
+        },
+        _ => quote! { surreal_devl::proxy::default::SurrealSerializer::serialize(Clone::clone(#arg)) },
+    }))
+}
+
+// #frag(fragment), a fragment is written unchanged, its parameters are either rendered or merged under new names
+fn frag(call: &syn::ExprCall, args: PlaceholderArgs, binding: Binding) -> syn::Result<TokenStream> {
+    args.ensure_options(&[])?;
+    let [fragment] = args.args.as_slice() else {
+        return Err(syn::Error::new_spanned(call, "Expected #frag(fragment)"));
+    };
+
+    let params = syn::Ident::new("params", proc_macro2::Span::mixed_site());
+    let reserved = syn::Ident::new("reserved", proc_macro2::Span::mixed_site());
+    let (renamed, render) = match binding {
+        Binding::Inline => (quote! {}, quote! { value.to_string() }),
+        Binding::Params | Binding::Capture => (quote! {
+            let mut renamed = std::collections::BTreeMap::<String, String>::new();
+        }, quote! {
+            renamed.entry(name.to_owned()).or_insert_with(|| {
+                let name = surreal_devl::surreal_fragment::next_param(&#params, #reserved);
+                #params.insert(name.clone(), value.clone());
+                format!("${}", name)
+            }).clone()
+        }),
+    };
+
+    Ok(quote! {{
+        let fragment: surreal_devl::surreal_fragment::SurrealFragment = Clone::clone(&(#fragment)).into();
+        #renamed
+        fragment.map_params(|name, value| #render)
+    }})
+}
+
+// The `id` of a record when its type implements `SurrealId`
+fn record_id_helpers() -> TokenStream {
+    quote! {
+        struct SurrealRecordId<'a, T>(&'a T);
+
+        trait SurrealRecordWithId {
+            fn surreal_record_id(&self) -> Option<surrealdb::sql::Thing>;
+        }
+
+        impl<T: surreal_devl::surreal_id::SurrealId> SurrealRecordWithId for SurrealRecordId<'_, T> {
+            fn surreal_record_id(&self) -> Option<surrealdb::sql::Thing> {
+                Some(surreal_devl::surreal_id::SurrealId::id(self.0))
+            }
+        }
+
+        trait SurrealRecordWithoutId {
+            fn surreal_record_id(&self) -> Option<surrealdb::sql::Thing>;
+        }
+
+        impl<T> SurrealRecordWithoutId for &SurrealRecordId<'_, T> {
+            fn surreal_record_id(&self) -> Option<surrealdb::sql::Thing> {
+                None
+            }
+        }
+    }
+}
+
+// #insert(&records) or #insert(table, &records), with an optional `chunk = n` to split large inputs
+fn insert(call: &syn::ExprCall, args: PlaceholderArgs, serialize: Serialize) -> syn::Result<TokenStream> {
+    args.ensure_options(&["chunk"])?;
+    let (table, records) = match args.args.as_slice() {
+        [records] => (quote! { None }, records),
+        [table, records] => (quote! { Some((#table).to_string()) }, records),
+        _ => return Err(syn::Error::new_spanned(call, "Expected #insert(&records) or #insert(table, &records)")),
+    };
+
+    // Without a table, the records must carry their id, otherwise the statement has no target
+    let with_id = match args.args.len() {
+        1 => quote_spanned! {records.span()=>
+            #[diagnostic::on_unimplemented(
+                message = "`{Self}` does not implement `SurrealId`, the insert has no table",
+                label = "use `#insert(table, ..)` for records without id"
+            )]
+            trait SurrealInsertWithId {}
+
+            impl<T: surreal_devl::surreal_id::SurrealId> SurrealInsertWithId for T {}
+
+            fn with_id<T: SurrealInsertWithId>(record: &T) -> &T {
+                record
+            }
+
+            let record = with_id(record);
+        },
+        _ => quote! {},
+    };
+
+    let chunk = match args.option("chunk") {
+        Some(chunk) => quote! { #chunk },
+        None => quote! { usize::MAX },
+    };
+    let helpers = record_id_helpers();
+    let value = serialize.value(&syn::parse_quote!(record));
+
+    Ok(quote! {{
+        let records = #records;
+        let table: Option<String> = #table;
+        let chunk: usize = #chunk;
+        {
+            #helpers
+
+            let mut table = table;
+            let mut values: Vec<surrealdb::sql::Value> = vec![];
+            for record in records {
+                #with_id
+                let id = (&SurrealRecordId(record)).surreal_record_id();
+                let mut value: surrealdb::sql::Value = #value;
+                if let (Some(id), surrealdb::sql::Value::Object(object)) = (&id, &mut value) {
+                    object.insert("id".to_owned(), surrealdb::sql::Value::Thing(id.clone()));
+                }
+
+                // Without a table, the table is the one of the first record id
+                if table.is_none() {
+                    table = id.map(|id| id.tb);
+                }
+
+                values.push(value);
+            }
+
+            let into = match table {
+                Some(table) => format!(" INTO {}", surrealdb::sql::Table::from(table)),
+                None => String::new(),
+            };
+
+            // An empty input is still a single statement, it returns an empty array
+            if values.is_empty() {
+                format!("INSERT{} []", into)
+            } else {
+                values
+                    .chunks(chunk.max(1))
+                    .map(|values| format!("INSERT{} {}", into, surrealdb::sql::Array::from(values.to_vec())))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            }
+        }
+    }})
+}
+
+// #upsert(&record), creates or replaces the whole record
+fn upsert(call: &syn::ExprCall, args: PlaceholderArgs) -> syn::Result<TokenStream> {
+    args.ensure_options(&[])?;
+    let [record] = args.args.as_slice() else {
+        return Err(syn::Error::new_spanned(call, "Expected #upsert(&record)"));
+    };
+
+    Ok(quote! {
+        format!("UPSERT {}", surreal_devl::surreal_statement::record(#record))
+    })
+}
+
+// #merge(&record), a partial update that never erases the fields left as None
+fn merge(call: &syn::ExprCall, args: PlaceholderArgs, serialize: Serialize) -> syn::Result<TokenStream> {
+    args.ensure_options(&[])?;
+    let [record] = args.args.as_slice() else {
+        return Err(syn::Error::new_spanned(call, "Expected #merge(&record)"));
+    };
+    let value = serialize.value(record);
+
+    Ok(quote! {{
+        fn without_none(value: surrealdb::sql::Value) -> surrealdb::sql::Value {
+            match value {
+                surrealdb::sql::Value::Object(object) => surrealdb::sql::Value::Object(
+                    object.0.into_iter()
+                        .filter(|(_, value)| !value.is_none())
+                        .map(|(key, value)| (key, without_none(value)))
+                        .collect::<std::collections::BTreeMap<_, _>>()
+                        .into(),
+                ),
+                value => value,
+            }
+        }
+
+        let value: surrealdb::sql::Value = #value;
+        surrealdb::sql::Data::MergeExpression(without_none(value)).to_string()
+    }})
+}
+
+// #set(&record), with optional `include = [...]` or `exclude = [...]` field lists.
+// Nested objects are written as dotted paths, so only the given leaves are updated
+fn set(call: &syn::ExprCall, args: PlaceholderArgs, serialize: Serialize) -> syn::Result<TokenStream> {
+    args.ensure_options(&["include", "exclude"])?;
+    let [record] = args.args.as_slice() else {
+        return Err(syn::Error::new_spanned(call, "Expected #set(&record)"));
+    };
+    let value = serialize.value(record);
+
+    let include = match args.names("include")? {
+        Some(names) => quote! { Some(&[#(#names),*]) },
+        None => quote! { None },
+    };
+    let exclude = args.names("exclude")?.unwrap_or_default();
+
+    Ok(quote! {{
+        let value: surrealdb::sql::Value = #value;
+        {
+            // A path matches a field when it is the field itself or one of its parents
+            fn matches(names: &[&str], path: &str) -> bool {
+                names.iter().any(|name| path == *name || path.starts_with(&format!("{}.", name)) || name.starts_with(&format!("{}.", path)))
+            }
+
+            fn assignments(prefix: &[String], value: surrealdb::sql::Value, include: Option<&[&str]>, exclude: &[&str], result: &mut Vec<String>) {
+                match value {
+                    // Empty nested objects are assigned as they are, the root itself is never assigned
+                    surrealdb::sql::Value::Object(object) if prefix.is_empty() || !object.is_empty() => {
+                        for (key, value) in object.0 {
+                            let mut path = prefix.to_vec();
+                            path.push(key);
+                            let name = path.join(".");
+                            if exclude.contains(&name.as_str()) || include.is_some_and(|include| !matches(include, &name)) {
+                                continue;
+                            }
+
+                            assignments(&path, value, include, exclude, result);
+                        }
+                    }
+                    _ if prefix.is_empty() => {}
+                    value => {
+                        let path = prefix.iter().map(|key| surrealdb::sql::Ident::from(key.as_str()).to_string()).collect::<Vec<_>>();
+                        result.push(format!("{} = {}", path.join("."), value));
+                    }
+                }
+            }
+
+            let include: Option<&[&str]> = #include;
+            let exclude: &[&str] = &[#(#exclude),*];
+            let mut result = vec![];
+            assignments(&[], value, include, exclude, &mut result);
+
+            // Without any field left the clause is skipped, `SET` alone is not a valid statement
+            match result.is_empty() {
+                true => String::new(),
+                false => format!("SET {}", result.join(", ")),
+            }
+        }
+    }})
+}
+
+// #fields(Type), the projection of a derived type, eg: `name, age, address.city`
+fn fields(call: &syn::ExprCall, args: PlaceholderArgs) -> syn::Result<TokenStream> {
+    args.ensure_options(&[])?;
+    let [Expr::Path(ty)] = args.args.as_slice() else {
+        return Err(syn::Error::new_spanned(call, "Expected #fields(Type)"));
+    };
+
+    Ok(quote! { <#ty as surreal_devl::surreal_table::SurrealTable>::surreal_fields() })
+}
+
+// #table(Type), the table of a derived type
+fn table(call: &syn::ExprCall, args: PlaceholderArgs) -> syn::Result<TokenStream> {
+    args.ensure_options(&[])?;
+    let [Expr::Path(ty)] = args.args.as_slice() else {
+        return Err(syn::Error::new_spanned(call, "Expected #table(Type)"));
+    };
+
+    Ok(quote! { surrealdb::sql::Table::from(<#ty as surreal_devl::surreal_table::SurrealTable>::TABLE) })
+}
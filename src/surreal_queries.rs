@@ -0,0 +1,181 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use surreal_devl::config::SurrealDeriveConfig;
+use syn::parse::{Parse, ParseStream};
+use syn::Token;
+
+use crate::config::{ConfigOverrides, MacroConfig};
+use crate::surreal_placeholder::Binding;
+use crate::surreal_quote::{instrumented, quote_template, template_file};
+
+// The file of the queries with an optional binding mode, eg: `"queries/users.surql", binding = params`,
+// after the same leading options as `surreal_quote!`
+pub struct QueriesInput {
+    overrides: ConfigOverrides,
+    path: syn::LitStr,
+    binding: Binding,
+}
+
+impl Parse for QueriesInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let overrides = input.parse()?;
+        let path = input.parse()?;
+        let mut binding = Binding::Inline;
+
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let option = input.parse::<syn::Ident>()?;
+            if option != "binding" {
+                return Err(syn::Error::new(option.span(), format!("Unknown option `{}`, expected `binding = params`", option)));
+            }
+
+            input.parse::<Token![=]>()?;
+            let value = input.parse::<syn::Ident>()?;
+            binding = match value.to_string().as_str() {
+                "inline" => Binding::Inline,
+                "params" => Binding::Params,
+                "capture" => Binding::Capture,
+                _ => return Err(syn::Error::new(value.span(), "Expected `binding = inline`, `binding = params` or `binding = capture`")),
+            };
+            input.parse::<Option<Token![,]>>()?;
+        }
+
+        Ok(Self { overrides, path, binding })
+    }
+}
+
+// A `-- name: ...` section of the file, the header is the signature of the function
+struct Section {
+    line: usize,
+    header: String,
+    template: String,
+}
+
+fn sections(content: &str) -> Result<Vec<Section>, String> {
+    let mut sections: Vec<Section> = vec![];
+
+    for (index, line) in content.lines().enumerate() {
+        if let Some(header) = line.trim().strip_prefix("-- name:") {
+            sections.push(Section {
+                line: index + 1,
+                header: header.trim().to_owned(),
+                template: String::new(),
+            });
+            continue;
+        }
+
+        match sections.last_mut() {
+            Some(section) => {
+                section.template.push_str(line);
+                section.template.push('\n');
+            }
+            // Only comments are allowed before the first query
+            None if line.trim().is_empty() || line.trim().starts_with("--") => {}
+            None => return Err(format!("Expected `-- name: <function>` before line {}", index + 1)),
+        }
+    }
+
+    Ok(sections)
+}
+
+// `find_users(min_age: i64) -> Vec<User>`, the parentheses may be omitted when there is no argument
+fn signature(header: &str) -> syn::Result<syn::Signature> {
+    let name_end = header.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(header.len());
+    let header = match header[name_end..].trim_start().starts_with(['(', '<']) {
+        true => header.to_owned(),
+        false => format!("{}(){}", &header[..name_end], &header[name_end..]),
+    };
+
+    syn::parse_str::<syn::Signature>(&format!("fn {}", header))
+}
+
+fn query(section: &Section, path: &syn::LitStr, binding: Binding, config: &SurrealDeriveConfig) -> syn::Result<TokenStream> {
+    let signature = signature(&section.header)
+        .map_err(|err| syn::Error::new(path.span(), format!("Invalid query `{}` on line {}: {}", section.header, section.line, err)))?;
+    let template = section.template.trim();
+    if template.is_empty() {
+        return Err(syn::Error::new(path.span(), format!("The query `{}` on line {} is empty", signature.ident, section.line)));
+    }
+
+    let mut args = vec![];
+    for input in signature.inputs.iter() {
+        match input {
+            syn::FnArg::Typed(syn::PatType { pat, .. }) if matches!(**pat, syn::Pat::Ident(_)) => args.push(pat.clone()),
+            _ => return Err(syn::Error::new(path.span(), format!("Invalid query `{}` on line {}, expected arguments such as `name: String`", signature.ident, section.line))),
+        }
+    }
+
+    let name = &signature.ident;
+    let generics = &signature.generics;
+    let where_clause = &signature.generics.where_clause;
+    let inputs = &signature.inputs;
+    let statement = quote_template(template, path.span(), binding, config);
+    let returns = match binding {
+        Binding::Inline => quote! { String },
+        Binding::Params | Binding::Capture => quote! { surreal_devl::surreal_fragment::SurrealFragment },
+    };
+    let doc = format!("Query `{}` of `{}`\n```sql\n{}\n```", name, path.value(), template);
+
+    let syn::ReturnType::Type(_, result) = &signature.output else {
+        return Ok(quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            pub fn #name #generics (#inputs) -> #returns #where_clause {
+                #statement
+            }
+        });
+    };
+
+    // The declared result type is read from the last statement of the query
+    let fetch = format_ident!("fetch_{}", name);
+    let fetch_doc = format!("Run the query `{}` of `{}` and deserialize the result of its last statement.", name, path.value());
+    let db = syn::Ident::new("db", Span::mixed_site());
+    let query = match binding {
+        Binding::Inline => quote! { #db.query(#name(#(#args),*)) },
+        Binding::Params | Binding::Capture => quote! {{
+            let fragment = #name(#(#args),*);
+            #db.query(fragment.statement).bind(fragment.params)
+        }},
+    };
+
+    let body = instrumented(
+        config,
+        &format!("{} of {}", name, path.value()),
+        quote! {
+            let mut response = #query.await?.check()?;
+            let index = response.num_statements().saturating_sub(1);
+            let result: surreal_devl::surreal_qr::SurrealQR = response.take(surreal_devl::surreal_qr::RPath::from(index))?;
+            result.deserialize::<#result>()
+        },
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[allow(dead_code)]
+        pub fn #name #generics (#inputs) -> #returns #where_clause {
+            #statement
+        }
+
+        #[doc = #fetch_doc]
+        #[allow(dead_code)]
+        pub async fn #fetch #generics (#db: &surrealdb::Surreal<impl surrealdb::Connection>, #inputs) -> Result<#result, surreal_devl::surreal_qr::SurrealResponseError> #where_clause {
+            #body
+        }
+    })
+}
+
+pub fn surreal_queries(input: QueriesInput) -> proc_macro::TokenStream {
+    let QueriesInput { overrides, path, binding } = input;
+    let config = overrides.apply(MacroConfig::get().derive);
+    let queries = template_file(&path).and_then(|(file, content)| {
+        let sections = sections(&content).map_err(|err| syn::Error::new(path.span(), err))?;
+        let queries = sections.iter().map(|section| query(section, &path, binding, &config)).collect::<syn::Result<Vec<_>>>()?;
+
+        // Including the file tells the compiler to rebuild when it changes
+        Ok(quote! {
+            const _: &str = include_str!(#file);
+            #(#queries)*
+        })
+    });
+
+    queries.unwrap_or_else(|err| err.to_compile_error()).into()
+}
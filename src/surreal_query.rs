@@ -0,0 +1,184 @@
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::Token;
+
+use crate::config::{ConfigOverrides, MacroConfig};
+use crate::surreal_placeholder::Binding;
+use crate::surreal_quote::{execution_span, instrumented, quote_template};
+
+// The result type followed by the template, eg: `Vec<User>, "SELECT * FROM #table(User)"`,
+// after the same leading options as `surreal_quote!`
+pub struct TypedQueryInput {
+    overrides: ConfigOverrides,
+    result: syn::Type,
+    template: syn::LitStr,
+}
+
+impl Parse for TypedQueryInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let overrides = input.parse()?;
+        let result = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let template = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+
+        Ok(Self { overrides, result, template })
+    }
+}
+
+pub fn surreal_query_as(input: TypedQueryInput) -> proc_macro::TokenStream {
+    let TypedQueryInput { overrides, result, template } = input;
+    let config = overrides.apply(MacroConfig::get().derive);
+    let statement = quote_template(&template.value(), template.span(), Binding::Inline, &config);
+    let span = execution_span(&config, &template.value()).map(|span| quote! { .with_span(#span) });
+
+    quote! {
+        surreal_devl::surreal_query::TypedQuery::<#result>::new(#statement)#span
+    }
+    .into()
+}
+
+// An entry of a transaction, eg: `Option<User> = "CREATE ONLY #record(&user)";`, `"LET $total = 1";`
+// or the guard `assert "$total > 0", "No record";`
+enum TransactionEntry {
+    Statement(Option<Box<syn::Type>>, syn::LitStr),
+    Guard(syn::LitStr, syn::LitStr),
+}
+
+// The entries after the same leading options as `surreal_quote!`
+pub struct TransactionInput {
+    overrides: ConfigOverrides,
+    entries: Vec<TransactionEntry>,
+}
+
+impl Parse for TransactionInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let overrides = input.parse()?;
+        let mut entries = vec![];
+        while !input.is_empty() {
+            let entry = if input.peek(syn::LitStr) {
+                TransactionEntry::Statement(None, input.parse()?)
+            } else if input.peek(syn::Ident) && input.peek2(syn::LitStr) {
+                let keyword = input.parse::<syn::Ident>()?;
+                if keyword != "assert" {
+                    return Err(syn::Error::new(keyword.span(), "Expected a guard, eg: `assert \"$total > 0\", \"No record\";`"));
+                }
+
+                let condition = input.parse()?;
+                input.parse::<Token![,]>()?;
+                TransactionEntry::Guard(condition, input.parse()?)
+            } else {
+                let result = Box::new(input.parse::<syn::Type>()?);
+                input.parse::<Token![=]>()?;
+                TransactionEntry::Statement(Some(result), input.parse()?)
+            };
+
+            entries.push(entry);
+            if !input.is_empty() {
+                input.parse::<Token![;]>()?;
+            }
+        }
+
+        Ok(Self { overrides, entries })
+    }
+}
+
+pub fn surreal_transaction(input: TransactionInput) -> proc_macro::TokenStream {
+    let config = input.overrides.apply(MacroConfig::get().derive);
+    let statement = syn::Ident::new("statement", proc_macro2::Span::mixed_site());
+    let count = syn::Ident::new("count", proc_macro2::Span::mixed_site());
+    let indexes = syn::Ident::new("indexes", proc_macro2::Span::mixed_site());
+    let mut statements = vec![];
+    let mut types = vec![];
+    let mut results = vec![];
+
+    for (index, entry) in input.entries.iter().enumerate() {
+        let (template, guard) = match entry {
+            TransactionEntry::Statement(_, template) => (template, None),
+            TransactionEntry::Guard(condition, message) => (condition, Some(message)),
+        };
+        let rendered = quote_template(&template.value(), template.span(), Binding::Inline, &config);
+
+        // A guard aborts the transaction when its condition is false.
+        // An entry may hold several statements, its result is the one of its last statement
+        statements.push(match guard {
+            Some(message) => quote! {
+                #statement.push_str(&format!(" IF !({}) {{ THROW {} }};", #rendered, surrealdb::sql::Value::from(#message)));
+                #count += 1;
+                #indexes.push(#count - 1);
+            },
+            None => quote! {{
+                let rendered = #rendered;
+                #count += surreal_devl::surreal_statement::count(&rendered);
+                #indexes.push(#count.max(1) - 1);
+                #statement.push_str(&format!(" {};", rendered));
+            }},
+        });
+
+        if let TransactionEntry::Statement(Some(result), _) = entry {
+            types.push(result);
+            results.push(quote! {{
+                let index = self.indexes[#index];
+                let result: surreal_devl::surreal_qr::SurrealQR = response.take(surreal_devl::surreal_qr::RPath::from(index)).map_err(|error| failed(index, error.into()))?;
+                result.deserialize::<#result>().map_err(|error| failed(index, error))?
+            }});
+        }
+    }
+
+    let execute = instrumented(
+        &config,
+        "transaction",
+        quote! {
+            let failed = |index: usize, error: surreal_devl::surreal_qr::SurrealResponseError| {
+                surreal_devl::surreal_qr::SurrealResponseError::StatementFailed(index, self.statement.clone(), Box::new(error))
+            };
+            let mut response = db.query(self.statement.as_str()).await?;
+            // The statements of a failed transaction are not executed, the error is the one of the statement which failed
+            let mut errors = response.take_errors().into_iter().collect::<Vec<_>>();
+            errors.sort_by_key(|(index, _)| *index);
+            let error = errors.iter().position(|(_, error)| !matches!(error, surrealdb::Error::Db(surrealdb::error::Db::QueryNotExecuted)));
+            if let Some(index) = error.or((!errors.is_empty()).then_some(0)) {
+                let (index, error) = errors.swap_remove(index);
+                return Err(failed(index, error.into()));
+            }
+
+            Ok::<_, surreal_devl::surreal_qr::SurrealResponseError>((#(#results,)*))
+        },
+    );
+
+    quote! {{
+        /// The statements of a transaction with the types of their results, run by `execute(&db)`.
+        #[must_use]
+        struct Transaction {
+            statement: String,
+            indexes: Vec<usize>,
+        }
+
+        #[allow(dead_code)]
+        impl Transaction {
+            /// Run the transaction and deserialize the result of every typed statement,
+            /// any error carries the index of the statement inside the transaction and its text.
+            async fn execute<C>(self, db: &surrealdb::Surreal<C>) -> Result<(#(#types,)*), surreal_devl::surreal_qr::SurrealResponseError>
+            where
+                C: surrealdb::Connection,
+            {
+                #execute
+            }
+        }
+
+        impl std::fmt::Display for Transaction {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.statement)
+            }
+        }
+
+        let mut #statement = String::from("BEGIN TRANSACTION;");
+        let mut #count = 0usize;
+        let mut #indexes = vec![];
+        #(#statements)*
+        #statement.push_str(" COMMIT TRANSACTION;");
+
+        Transaction { statement: #statement, indexes: #indexes }
+    }}
+    .into()
+}
@@ -1,103 +1,667 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use surreal_devl::config::SurrealDeriveConfig;
+use syn::parse::{Parse, ParseStream};
 
-pub fn surreal_quote(input: String) -> proc_macro::TokenStream {
-    let config = SurrealDeriveConfig::get();
-
-    let mut chars = input.chars();
-    let mut output = String::new();
-    let mut values = Vec::new();
-
-    while let Some(c) = chars.next() {
-        match c {
-            '#' => {
-                // Handle '#': This is a special case in the input string.
-                output.push_str("{}");
-                let mut content = String::new();
-                'outer: while let Some(c) = chars.next() {
-                    match c {
-                        '(' => {
-                            // Handle the start of a block.
-                            content.push('(');
-                            let mut depth = 1;
-                            while let Some(c) = chars.next() {
-                                content.push(c);
-                                match c {
-                                    '(' => depth += 1,
-                                    ')' => {
-                                        depth -= 1;
-                                        if depth == 0 {
-                                            // End of the block.
-                                            break 'outer;
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                        '\'' | '"' => {
-                            output.push(c);
-                            break;
-                        }
-                        ';' | ',' => {
-                            output.push(c);
-                            output = output.trim().to_string();
-                            break;
+use crate::config::{ConfigOverrides, MacroConfig};
+use crate::surreal_dump;
+use crate::surreal_placeholder::{expand_placeholder, expand_redacted, Binding};
+
+// A parsed template, the text is kept with its whitespaces collapsed outside of literals and comments
+enum Segment {
+    Text(String),
+    // The content of a placeholder, eg: `val(&user)` for `#val(&user)`
+    Value(String),
+    // #if(condition){ ... } with an optional #else{ ... }
+    If {
+        condition: String,
+        then: Vec<Segment>,
+        otherwise: Vec<Segment>,
+    },
+    // A SurrealQL parameter, eg: `$name`, declared when the statement defines it, eg: `LET $name = ...`,
+    // escaped when written `$$name`
+    Param {
+        name: String,
+        declared: bool,
+        escaped: bool,
+    },
+    // #for(pattern in iterator){ ... } with an optional #sep("...")
+    For {
+        binding: String,
+        body: Vec<Segment>,
+        separator: Option<String>,
+    },
+}
+
+struct TemplateParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl TemplateParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn starts_with(&self, pattern: &str) -> bool {
+        pattern.chars().enumerate().all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    // Read a balanced block starting at the current `open` character, returns its content without the delimiters
+    fn block(&mut self, open: char, close: char) -> Option<String> {
+        if self.peek() != Some(open) {
+            return None;
+        }
+
+        let start = self.pos + 1;
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            // A rust string may contain the delimiters, eg: #val(&")")
+            if c == '"' {
+                self.literal('"', '"').ok()?;
+                continue;
+            }
+
+            self.pos += 1;
+            if c == open {
+                depth += 1;
+            } else if c == close {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(self.chars[start..self.pos - 1].iter().collect());
+                }
+            }
+        }
+
+        None
+    }
+
+    // A quoted literal, copied as is with its delimiters, a backslash escapes the next character
+    fn literal(&mut self, open: char, close: char) -> Result<String, String> {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => self.pos += 1,
+                c if c == close => return Ok(self.chars[start..self.pos].iter().collect()),
+                _ => {}
+            }
+        }
+
+        Err(format!("Unclosed literal, expected `{}` to close `{}`", close, open))
+    }
+
+    // A comment, copied as is. A line comment keeps its line break so the rest of the statement is not commented out
+    fn comment(&mut self) -> String {
+        let start = self.pos;
+        let end = match self.starts_with("/*") {
+            true => "*/",
+            false => "\n",
+        };
+
+        self.pos += 2;
+        while self.pos < self.chars.len() && !self.starts_with(end) {
+            self.pos += 1;
+        }
+
+        self.pos = (self.pos + end.len()).min(self.chars.len());
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    // Segments until the end of the template, or until the `}` closing the current block
+    fn segments(&mut self, in_block: bool) -> Result<Vec<Segment>, String> {
+        let mut segments = vec![];
+        let mut text = String::new();
+        let mut depth = 0;
+
+        while let Some(c) = self.peek() {
+            match c {
+                // An escaped `#`
+                '#' if self.starts_with("##") => {
+                    text.push('#');
+                    self.pos += 2;
+                }
+                '#' => {
+                    self.pos += 1;
+                    let segment = self.placeholder()?;
+                    // #else and #sep(...) may be separated from the block they follow by whitespaces
+                    let attached = matches!(&segment, Segment::Text(keyword) if keyword == "else" || keyword.starts_with("sep("));
+                    if attached && text.trim().is_empty() {
+                        text.clear();
+                    } else if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+
+                    match (segment, segments.last_mut()) {
+                        (Segment::Text(keyword), Some(Segment::If { otherwise, .. })) if keyword == "else" => {
+                            *otherwise = self.body("#else")?;
                         }
-                        ' ' | '\n' | '\r' | '\t' => {
-                            output = output.trim().to_string();
-                            output.push(' ');
-                            break;
+                        (Segment::Text(keyword), _) if keyword == "else" => return Err("#else must follow the block of an #if(...){ ... }".to_owned()),
+                        (Segment::Text(separator), Some(Segment::For { separator: for_separator @ None, .. })) if separator.starts_with("sep(") => {
+                            let separator = syn::parse_str::<syn::LitStr>(&separator[4..separator.len() - 1])
+                                .map_err(|_| format!("Invalid #{}, expected a string literal, eg: #sep(\", \")", separator))?;
+                            *for_separator = Some(separator.value());
                         }
-                        _ => {
-                            content.push(c);
+                        (Segment::Text(separator), _) if separator.starts_with("sep(") => return Err("#sep(...) must follow the block of a #for(...){ ... }".to_owned()),
+                        (segment, _) => segments.push(segment),
+                    }
+                }
+                // An escaped parameter, written as `$name` and never captured, eg: a parameter bound later with `.bind(...)`
+                '$' if self.starts_with("$$") && !self.chars.get(self.pos + 2).is_some_and(|c| c.is_alphabetic() || *c == '_') => {
+                    text.push('$');
+                    self.pos += 2;
+                }
+                '$' if self.chars.get(self.pos + 1).is_some_and(|c| c.is_alphabetic() || *c == '_' || *c == '$') => {
+                    let escaped = self.starts_with("$$");
+                    self.pos += if escaped { 2 } else { 1 };
+                    let start = self.pos;
+                    while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                        self.pos += 1;
+                    }
+
+                    let declared = declares_param(&text);
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Segment::Param {
+                        name: self.chars[start..self.pos].iter().collect(),
+                        declared,
+                        escaped,
+                    });
+                }
+                // Strings and escaped identifiers are kept as is, eg: 'a  #b' or `first name`
+                '\'' | '"' | '`' => text.push_str(&self.literal(c, c)?),
+                '⟨' => text.push_str(&self.literal(c, '⟩')?),
+                '-' if self.starts_with("--") => text.push_str(&self.comment()),
+                '/' if self.starts_with("//") || self.starts_with("/*") => text.push_str(&self.comment()),
+                '{' => {
+                    depth += 1;
+                    text.push(c);
+                    self.pos += 1;
+                }
+                '}' if in_block && depth == 0 => break,
+                '}' => {
+                    depth -= 1;
+                    text.push(c);
+                    self.pos += 1;
+                }
+                ' ' | '\n' | '\r' | '\t' => {
+                    if !text.ends_with([' ', '\n']) {
+                        text.push(' ');
+                    }
+                    self.pos += 1;
+                }
+                _ => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        Ok(segments)
+    }
+
+    // The `{ ... }` following a control placeholder
+    fn body(&mut self, keyword: &str) -> Result<Vec<Segment>, String> {
+        if self.peek() != Some('{') {
+            return Err(format!("Expected a block after {}, eg: {}{{ ... }}", keyword, keyword));
+        }
+
+        self.pos += 1;
+        let mut body = self.segments(true)?;
+        if self.peek() != Some('}') {
+            return Err(format!("Unclosed block of {}, expected `}}`", keyword));
+        }
+
+        // The whitespaces around the content of a block are only there for readability, eg: `#if(x){ AND x }`
+        if let Some(Segment::Text(text)) = body.first_mut() {
+            *text = text.trim_start_matches(' ').to_owned();
+        }
+        if let Some(Segment::Text(text)) = body.last_mut() {
+            *text = text.trim_end_matches(' ').to_owned();
+        }
+        body.retain(|segment| !matches!(segment, Segment::Text(text) if text.is_empty()));
+
+        self.pos += 1;
+        Ok(body)
+    }
+
+    // Everything following a `#`, control keywords are returned as text and attached by the caller
+    fn placeholder(&mut self) -> Result<Segment, String> {
+        for keyword in ["if", "for"] {
+            if self.starts_with(&format!("{}(", keyword)) {
+                self.pos += keyword.len();
+                let header = self.block('(', ')').ok_or_else(|| format!("Unclosed #{}(...), expected `)`", keyword))?;
+                let body = self.body(&format!("#{}({})", keyword, header))?;
+
+                return Ok(match keyword {
+                    "if" => Segment::If {
+                        condition: header,
+                        then: body,
+                        otherwise: vec![],
+                    },
+                    _ => Segment::For {
+                        binding: header,
+                        body,
+                        separator: None,
+                    },
+                });
+            }
+        }
+
+        if self.starts_with("else{") {
+            self.pos += 4;
+            return Ok(Segment::Text("else".to_owned()));
+        }
+
+        let mut content = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '(' => {
+                    let block = self.block('(', ')').ok_or_else(|| format!("Unclosed #{}(...), expected `)`", content))?;
+                    content.push_str(&format!("({})", block));
+                    break;
+                }
+                '\'' | '"' | ';' | ',' | '{' | '}' | ' ' | '\n' | '\r' | '\t' => break,
+                _ => {
+                    content.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        if content.starts_with("sep(") {
+            return Ok(Segment::Text(content));
+        }
+
+        if content.is_empty() {
+            return Err("Expected a placeholder after `#`, eg: #val(&user)".to_owned());
+        }
+
+        Ok(Segment::Value(content))
+    }
+}
+
+// Rust statements appending the segments to `statement`.
+// A space between two pieces of text is only written once, and never at the start or at the end
+// With `mask`, the same statement is appended to `logged` with the sensitive fields of the records as `***`
+fn generate(segments: &[Segment], span: Span, binding: Binding, mask: bool) -> syn::Result<TokenStream> {
+    // The generated variables are not visible from the placeholders, eg: a local variable named `statement`
+    let statement = syn::Ident::new("statement", Span::mixed_site());
+    let space = syn::Ident::new("space", Span::mixed_site());
+    let index = syn::Ident::new("index", Span::mixed_site());
+    let logged = syn::Ident::new("logged", Span::mixed_site());
+    let rendered = syn::Ident::new("rendered", Span::mixed_site());
+
+    let mut output = vec![];
+    let mut format = String::new();
+    let mut values = vec![];
+    let mut leading_space = false;
+
+    let flush = |format: &mut String, values: &mut Vec<TokenStream>, leading_space: &mut bool, output: &mut Vec<TokenStream>| {
+        let trailing_space = format.ends_with(' ');
+        let content = format.trim_matches(' ');
+        if content.is_empty() && values.is_empty() {
+            if *leading_space || trailing_space {
+                output.push(quote! { #space = true; });
+            }
+        } else {
+            let leading = *leading_space || format.starts_with(' ');
+            output.push(match mask {
+                // Each value is rendered for the statement and for the logs
+                true => {
+                    let count = values.len();
+                    let statement_values = (0..count).map(|i| quote! { #rendered[#i].0 });
+                    let logged_values = (0..count).map(|i| quote! { #rendered[#i].1 });
+                    let rendered_decl = match count {
+                        0 => quote! {},
+                        _ => quote! { let #rendered: [(String, String); #count] = [#(#values),*]; },
+                    };
+                    quote! {
+                        #rendered_decl
+                        if !#statement.is_empty() && (#space || #leading) {
+                            #statement.push(' ');
+                            #logged.push(' ');
                         }
+                        #statement.push_str(&format!(#content, #(#statement_values),*));
+                        #logged.push_str(&format!(#content, #(#logged_values),*));
+                        #space = #trailing_space;
                     }
                 }
+                false => quote! {
+                    if !#statement.is_empty() && (#space || #leading) {
+                        #statement.push(' ');
+                    }
+                    #statement.push_str(&format!(#content, #(#values),*));
+                    #space = #trailing_space;
+                },
+            });
+        }
 
-                values.push(content);
+        format.clear();
+        values.clear();
+        *leading_space = false;
+    };
+
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => {
+                if format.is_empty() && values.is_empty() && text.starts_with(' ') {
+                    leading_space = true;
+                }
+                format.push_str(&text.replace('{', "{{").replace('}', "}}"));
+            }
+            Segment::Param { name, .. } => {
+                format.push('$');
+                format.push_str(name);
+            }
+            Segment::Value(content) => {
+                format.push_str("{}");
+                let value = expand_placeholder(content, span, binding);
+                values.push(match mask {
+                    true => {
+                        let rendered = syn::Ident::new("rendered", Span::mixed_site());
+                        let redacted = match expand_redacted(content) {
+                            Some(redacted) => quote! { #redacted.to_string() },
+                            None => quote! { #rendered.clone() },
+                        };
+                        quote! {{
+                            let #rendered = (#value).to_string();
+                            let #logged = #redacted;
+                            (#rendered, #logged)
+                        }}
+                    }
+                    false => value,
+                });
+            }
+            Segment::If { condition, then, otherwise } => {
+                flush(&mut format, &mut values, &mut leading_space, &mut output);
+                let condition = syn::parse_str::<syn::Expr>(condition)
+                    .map_err(|err| syn::Error::new(span, format!("Invalid condition in #if({}): {}", condition, err)))?;
+                let then = generate(then, span, binding, mask)?;
+                let otherwise = generate(otherwise, span, binding, mask)?;
+
+                output.push(quote! {
+                    if #condition {
+                        #then
+                    } else {
+                        #otherwise
+                    }
+                });
             }
-            ' ' | '\n' | '\r' | '\t' => {
-                output = output.trim().to_string();
-                output.push(' '); // more optimize
+            Segment::For { binding: header, body, separator } => {
+                flush(&mut format, &mut values, &mut leading_space, &mut output);
+                let (pattern, iterator) = syn::parse::Parser::parse_str(
+                    |input: syn::parse::ParseStream| {
+                        let pattern = syn::Pat::parse_single(input)?;
+                        input.parse::<syn::Token![in]>()?;
+                        Ok((pattern, input.parse::<syn::Expr>()?))
+                    },
+                    header,
+                ).map_err(|err| syn::Error::new(span, format!("Invalid #for({}), expected #for(item in &items): {}", header, err)))?;
+                let body = generate(body, span, binding, mask)?;
+                let separator = match separator {
+                    Some(separator) => generate(&[Segment::Text(separator.clone())], span, binding, mask)?,
+                    None => quote! {},
+                };
+
+                output.push(quote! {
+                    for (#index, #pattern) in (#iterator).into_iter().enumerate() {
+                        if #index > 0 {
+                            #separator
+                        }
+                        #body
+                    }
+                });
             }
-            _ => {
-                output.push(c);
+        }
+    }
+
+    flush(&mut format, &mut values, &mut leading_space, &mut output);
+
+    Ok(quote! { #(#output)* })
+}
+
+// Whether the text before a parameter declares it, eg: `LET $total`, `FOR $user IN` or `DEFINE PARAM OVERWRITE $tax_rate`
+fn declares_param(text: &str) -> bool {
+    // Only whitespaces separate the keywords, `param = $name` compares a field
+    let mut words = text
+        .split_whitespace()
+        .rev()
+        .map(|word| word.rsplit(|c: char| !c.is_alphanumeric()).next().unwrap_or_default().to_ascii_lowercase())
+        .peekable();
+    match words.peek().map(String::as_str) {
+        Some("let" | "for" | "param") => true,
+        Some("overwrite") => words.nth(1).is_some_and(|word| word == "param"),
+        Some("exists") => words.find(|word| !["exists", "not", "if"].contains(&word.as_str())).is_some_and(|word| word == "param"),
+        _ => false,
+    }
+}
+
+// Parameters defined by SurrealDB, never captured from the local variables
+const BUILTIN_PARAMS: &[&str] = &["access", "after", "auth", "before", "event", "input", "parent", "request", "scope", "session", "this", "token", "value"];
+
+// The `$name` parameters of the template, with whether the statement declares them and whether they are escaped
+fn param_names(segments: &[Segment], result: &mut Vec<(String, bool, bool)>) {
+    for segment in segments {
+        match segment {
+            Segment::Param { name, declared, escaped } => result.push((name.clone(), *declared, *escaped)),
+            Segment::If { then, otherwise, .. } => {
+                param_names(then, result);
+                param_names(otherwise, result);
             }
+            Segment::For { body, .. } => param_names(body, result),
+            Segment::Text(_) | Segment::Value(_) => {}
         }
     }
+}
+
+// Every `$name` of the template, the escaped ones included, a generated parameter never takes their name
+fn reserved_names(segments: &[Segment]) -> std::collections::BTreeSet<String> {
+    let mut result = vec![];
+    param_names(segments, &mut result);
+
+    result.into_iter().map(|(name, _, _)| name).collect()
+}
+
+// The `$name` parameters bound from the local variables, except the builtin, declared and escaped ones
+fn captured(segments: &[Segment]) -> std::collections::BTreeSet<String> {
+    let mut result = vec![];
+    param_names(segments, &mut result);
+    let declared = result.iter().filter(|(_, declared, _)| *declared).map(|(name, _, _)| name.clone()).collect::<Vec<_>>();
+
+    result.into_iter()
+        .filter(|(name, _, escaped)| !escaped && !declared.contains(name) && !BUILTIN_PARAMS.contains(&name.as_str()))
+        .map(|(name, _, _)| name)
+        .collect()
+}
+
+// A stable id of the template, the same template has the same id at every call site and on every build
+fn template_id(template: &str) -> String {
+    let hash = template.bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
+
+// The template with its leading options, eg: `log = false, namespace = "billing", "SELECT ..."`
+pub struct QuoteInput {
+    overrides: ConfigOverrides,
+    template: syn::LitStr,
+}
+
+impl Parse for QuoteInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let overrides = input.parse()?;
+        let template = input.parse()?;
+
+        Ok(Self { overrides, template })
+    }
+}
+
+pub fn surreal_quote(input: QuoteInput, binding: Binding) -> proc_macro::TokenStream {
+    let QuoteInput { overrides, template } = input;
+    let config = overrides.apply(MacroConfig::get().derive);
+
+    quote_template(&template.value(), template.span(), binding, &config).into()
+}
+
+// The block building the statement of a template, mistakes are reported at `span`
+pub fn quote_template(template: &str, span: Span, binding: Binding, config: &SurrealDeriveConfig) -> TokenStream {
+    let mut parser = TemplateParser {
+        chars: template.chars().collect(),
+        pos: 0,
+    };
+    let segments = match parser.segments(false) {
+        Ok(segments) => segments,
+        Err(err) => return syn::Error::new(span, err).to_compile_error(),
+    };
+    // Only the text log shows the statement, the `tracing` event never contains the values.
+    // The bound parameters are never part of the statement
+    let mask = config.enable_log && !cfg!(feature = "tracing") && binding == Binding::Inline;
+    let statement = match generate(&segments, span, binding, mask) {
+        Ok(statement) => statement,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let statement_var = syn::Ident::new("statement", Span::mixed_site());
+    let space = syn::Ident::new("space", Span::mixed_site());
+    let params = syn::Ident::new("params", Span::mixed_site());
+    let logged = syn::Ident::new("logged", Span::mixed_site());
+    let logged_decl = match mask {
+        true => quote! { let mut #logged = String::new(); },
+        false => quote! {},
+    };
+    // A fragment carries its bound parameters along with the statement
+    let (params_decl, result) = match binding {
+        Binding::Inline => (quote! {}, quote! { #statement_var }),
+        Binding::Params | Binding::Capture => (
+            {
+                let names = reserved_names(&segments).into_iter();
+                let reserved = syn::Ident::new("reserved", Span::mixed_site());
+                quote! {
+                    let mut #params = std::collections::BTreeMap::<String, surrealdb::sql::Value>::new();
+                    let #reserved: &[&str] = &[#(#names),*];
+                }
+            },
+            quote! { surreal_devl::surreal_fragment::SurrealFragment::new(#statement_var, #params) },
+        ),
+    };
+    // Like `format!`, the variables are captured where the macro is called
+    let captures = match binding {
+        Binding::Capture => captured(&segments).into_iter().filter_map(|name| {
+            // Keywords such as `$type` are not rust variables
+            let mut variable = syn::parse_str::<syn::Ident>(&name).ok()?;
+            variable.set_span(span);
 
-    output = output.trim().to_owned();
-    let values = values
-        .clone()
-        .into_iter()
-        .map(|it| syn::parse_str::<TokenStream>(&it).unwrap());
+            Some(quote! {
+                #params.insert(#name.to_owned(), surreal_devl::proxy::default::SurrealSerializer::serialize((#variable).clone()));
+            })
+        }).collect(),
+        _ => vec![],
+    };
+    let log_namespace = &config.namespace;
+    let debug_log = match (cfg!(feature = "tracing"), config.enable_log) {
+        // A structured event, it never contains the values, only the names of the bound parameters
+        (true, true) => {
+            let template_id = template_id(template);
+            let param_names = match binding {
+                Binding::Inline => quote! { Vec::<&String>::new() },
+                Binding::Params | Binding::Capture => quote! { #params.keys().collect::<Vec<_>>() },
+            };
 
-    let log_namespace = config.namespace;
-    let log_fn = syn::parse_str::<TokenStream>(config.info_log_macro.as_str()).unwrap();
-    let debug_log = match config.enable_log {
-        true => {
             quote! {
-                #log_fn!("{}: {}", #log_namespace, statement);
+                tracing::event!(
+                    target: #log_namespace,
+                    tracing::Level::INFO,
+                    file = file!(),
+                    line = line!(),
+                    template_id = #template_id,
+                    template = #template,
+                    length = #statement_var.len(),
+                    params = ?#param_names,
+                    "surreal statement"
+                );
+            }
+        }
+        (false, true) => {
+            let log_fn = syn::parse_str::<TokenStream>(config.info_log_macro.as_str()).unwrap();
+            let logged = if mask { &logged } else { &statement_var };
+            quote! {
+                #log_fn!("{}: {}", #log_namespace, #logged);
             }
         }
-        false => {
+        (_, false) => {
             quote! {}
         }
     };
 
-    let output: proc_macro::TokenStream = (quote::quote! {{
+    let output = quote! {{
         use surreal_devl::surreal_statement::*;
-        let statement = format!(#output, #(#values),*);
+        let mut #statement_var = String::new();
+        let mut #space = false;
+        #params_decl
+        #logged_decl
+        #(#captures)*
+        #statement
+        let _ = #space;
         #debug_log
-        statement
-    }})
-    .into();
+        #result
+    }};
 
     if config.enable_compile_log {
-        println!("DEBUG: {}  {}", log_namespace, output);
+        surreal_dump::compile_log(log_namespace, &output);
     }
 
     output
 }
+
+// The `tracing` span covering the execution of a query, `None` when the runtime log is off
+pub fn execution_span(config: &SurrealDeriveConfig, query: &str) -> Option<TokenStream> {
+    if !cfg!(feature = "tracing") || !config.enable_log {
+        return None;
+    }
+
+    let log_namespace = &config.namespace;
+    Some(quote! {
+        tracing::info_span!(target: #log_namespace, "surreal query", file = file!(), line = line!(), query = #query)
+    })
+}
+
+// The body of an async function run inside the span of `execution_span`, its last expression gives the type of the result
+pub fn instrumented(config: &SurrealDeriveConfig, query: &str, body: TokenStream) -> TokenStream {
+    match execution_span(config, query) {
+        Some(span) => quote! { tracing::Instrument::instrument(async move { #body }, #span).await },
+        None => body,
+    }
+}
+
+// Read a template file, the path is relative to the root of the crate being compiled. Returns the absolute path and the content
+pub fn template_file(input: &syn::LitStr) -> syn::Result<(String, String)> {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = std::path::Path::new(&root).join(input.value());
+    let content = std::fs::read_to_string(&path)
+        .map_err(|err| syn::Error::new(input.span(), format!("Failed to read the template {}: {}", path.display(), err)))?;
+
+    Ok((path.to_string_lossy().into_owned(), content))
+}
+
+pub fn surreal_quote_file(input: QuoteInput, binding: Binding) -> proc_macro::TokenStream {
+    let QuoteInput { overrides, template } = input;
+    let (path, content) = match template_file(&template) {
+        Ok(file) => file,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let config = overrides.apply(MacroConfig::get().derive);
+    let statement = quote_template(&content, template.span(), binding, &config);
+    // Including the file tells the compiler to rebuild when it changes
+    quote! {{
+        const _: &str = include_str!(#path);
+        #statement
+    }}
+    .into()
+}
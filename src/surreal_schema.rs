@@ -0,0 +1,99 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use surreal_devl::config::SurrealDeriveConfig;
+use syn::{GenericArgument, PathArguments, Type};
+
+use crate::attributes::{extract_field_attributes, SurrealDeriveAttribute};
+
+// Resolve the SurrealQL type of a rust type, None when the type is unknown (eg: user defined structs)
+fn surreal_kind(ty: &Type) -> Option<String> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        Type::Reference(reference) => return surreal_kind(&reference.elem),
+        _ => return None,
+    };
+
+    let inner = || match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+
+    let kind = match segment.ident.to_string().as_str() {
+        "String" | "str" | "Strand" => "string".to_owned(),
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => "int".to_owned(),
+        "f32" | "f64" => "float".to_owned(),
+        "Decimal" => "decimal".to_owned(),
+        "bool" => "bool".to_owned(),
+        "DateTime" | "Datetime" => "datetime".to_owned(),
+        "Duration" => "duration".to_owned(),
+        "Uuid" => "uuid".to_owned(),
+        "Bytes" => "bytes".to_owned(),
+        "Geometry" => "geometry".to_owned(),
+        "Thing" | "Link" => "record".to_owned(),
+        "Box" => return surreal_kind(inner()?),
+        "Option" => format!("option<{}>", surreal_kind(inner()?)?),
+        "Vec" => match inner().and_then(surreal_kind) {
+            Some(kind) => format!("array<{}>", kind),
+            None => "array".to_owned(),
+        },
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+pub fn surreal_schema_impl(ast: &syn::ItemStruct, attributes: &SurrealDeriveAttribute, config: &SurrealDeriveConfig) -> TokenStream {
+    let struct_name = &ast.ident;
+
+    let mut fields = vec![];
+    let mut indexes = vec![];
+    for field in ast.fields.iter() {
+        let field_attrs = extract_field_attributes(field);
+        if field_attrs.skip_serializing || field_attrs.record_id {
+            continue;
+        }
+
+        let field_name = field.ident.as_ref().expect("Failed to process variable name, the ident could not be empty");
+        let db_name = field_attrs.db_name(field_name, config);
+        let kind = field_attrs.db_type.clone()
+            .or_else(|| surreal_kind(&field.ty))
+            .unwrap_or_else(|| "any".to_owned());
+
+        if field_attrs.index {
+            let index_name = format!("{}_index", db_name.replace('.', "_"));
+            let unique = field_attrs.unique;
+            indexes.push(quote! { (#index_name, #db_name, #unique) });
+        }
+
+        fields.push(quote! { (#db_name, #kind) });
+    }
+
+    if attributes.version.is_some() {
+        fields.push(quote! { ("_v", "int") });
+    }
+
+    let schema_consts = quote! {
+        const SCHEMA_FIELDS: &[surreal_devl::surreal_schema::SchemaField] = &[#(#fields),*];
+        const SCHEMA_INDEXES: &[surreal_devl::surreal_schema::SchemaIndex] = &[#(#indexes),*];
+    };
+
+    quote! {
+        impl #struct_name {
+            /// `DEFINE FIELD` and `DEFINE INDEX` statements describing this type inside `table`.
+            pub fn surreal_schema(table: &str) -> Vec<String> {
+                #schema_consts
+                surreal_devl::surreal_schema::schema(table, SCHEMA_FIELDS, SCHEMA_INDEXES)
+            }
+
+            /// Compares the result of `INFO FOR TABLE <table>` with the schema of this type,
+            /// returns the ordered statements to apply, each flagged `true` when it is destructive.
+            pub fn surreal_migration(table: &str, info: &surrealdb::sql::Value) -> Vec<(String, bool)> {
+                #schema_consts
+                surreal_devl::surreal_schema::migration(table, SCHEMA_FIELDS, SCHEMA_INDEXES, info)
+            }
+        }
+    }
+}
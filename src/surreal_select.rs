@@ -0,0 +1,128 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use surreal_devl::config::SurrealDeriveConfig;
+
+use crate::surreal_quote::instrumented;
+
+pub fn surreal_select_impl(ast: &syn::ItemStruct, config: &SurrealDeriveConfig) -> TokenStream {
+    let vis = &ast.vis;
+    let struct_name = &ast.ident;
+    let select_name = format_ident!("{}Select", struct_name);
+    let fetch = instrumented(
+        config,
+        &format!("{}::select()", struct_name),
+        quote! {
+            let records: surreal_devl::surreal_qr::SurrealQR = db.query(self.to_string()).await?.take(surreal_devl::surreal_qr::RPath::from(0))?;
+            let mut result = vec![];
+            for record in records.array()?.iter().flat_map(|records| records.iter()) {
+                result.push(<#struct_name as surreal_devl::proxy::default::SurrealDeserializer>::deserialize(record)?);
+            }
+
+            Ok::<_, surreal_devl::surreal_qr::SurrealResponseError>(result)
+        },
+    );
+    let doc = format!("`SELECT` statement loading [`{}`] records, built from `{}::select()`.", struct_name, struct_name);
+
+    quote! {
+        #[doc = #doc]
+        #[allow(dead_code)]
+        #[derive(Clone, Debug, Default, PartialEq)]
+        #vis struct #select_name {
+            conditions: Vec<surrealdb::sql::Value>,
+            order: Vec<(surrealdb::sql::Idiom, bool)>,
+            limit: Option<u64>,
+            start: Option<u64>,
+            fetch: Vec<surrealdb::sql::Idiom>,
+        }
+
+        #[allow(dead_code)]
+        impl #select_name {
+            /// Keep the records matching the condition, several filters are joined with `AND`.
+            #vis fn filter(mut self, condition: surrealdb::sql::Value) -> Self {
+                self.conditions.push(condition);
+                self
+            }
+
+            /// Keep the records matching any of the conditions, eg: `(age < 18 OR age > 65)`.
+            #vis fn filter_any(mut self, conditions: impl IntoIterator<Item = surrealdb::sql::Value>) -> Self {
+                let condition = conditions.into_iter().reduce(|l, r| {
+                    surrealdb::sql::Value::Expression(Box::new(surrealdb::sql::Expression::new(l, surrealdb::sql::Operator::Or, r)))
+                });
+
+                if let Some(condition) = condition {
+                    self.conditions.push(surrealdb::sql::Value::Subquery(Box::new(surrealdb::sql::Subquery::Value(condition))));
+                }
+
+                self
+            }
+
+            /// Sort by the field in ascending order.
+            #vis fn order_by(mut self, field: impl Into<surrealdb::sql::Idiom>) -> Self {
+                self.order.push((field.into(), true));
+                self
+            }
+
+            /// Sort by the field in descending order.
+            #vis fn order_by_desc(mut self, field: impl Into<surrealdb::sql::Idiom>) -> Self {
+                self.order.push((field.into(), false));
+                self
+            }
+
+            /// Return at most `limit` records.
+            #vis fn limit(mut self, limit: u64) -> Self {
+                self.limit = Some(limit);
+                self
+            }
+
+            /// Skip the first `start` records.
+            #vis fn start(mut self, start: u64) -> Self {
+                self.start = Some(start);
+                self
+            }
+
+            /// Replace the record ids of the field by the records themselves, `FETCH field`.
+            #vis fn fetch_related(mut self, field: impl Into<surrealdb::sql::Idiom>) -> Self {
+                self.fetch.push(field.into());
+                self
+            }
+
+            /// Run the statement and deserialize the selected records.
+            #vis async fn fetch<C>(self, db: &surrealdb::Surreal<C>) -> Result<Vec<#struct_name>, surreal_devl::surreal_qr::SurrealResponseError>
+            where
+                C: surrealdb::Connection,
+            {
+                #fetch
+            }
+        }
+
+        impl std::fmt::Display for #select_name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "SELECT * FROM {}", surrealdb::sql::Table::from(<#struct_name as surreal_devl::surreal_table::SurrealTable>::TABLE))?;
+                if !self.conditions.is_empty() {
+                    let conditions = self.conditions.iter().map(|condition| condition.to_string()).collect::<Vec<_>>();
+                    write!(f, " WHERE {}", conditions.join(" AND "))?;
+                }
+
+                if !self.order.is_empty() {
+                    let order = self.order.iter().map(|(field, asc)| format!("{} {}", field, if *asc { "ASC" } else { "DESC" })).collect::<Vec<_>>();
+                    write!(f, " ORDER BY {}", order.join(", "))?;
+                }
+
+                if let Some(limit) = self.limit {
+                    write!(f, " LIMIT {}", limit)?;
+                }
+
+                if let Some(start) = self.start {
+                    write!(f, " START {}", start)?;
+                }
+
+                if !self.fetch.is_empty() {
+                    let fetch = self.fetch.iter().map(|field| field.to_string()).collect::<Vec<_>>();
+                    write!(f, " FETCH {}", fetch.join(", "))?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use surreal_devl::config::SurrealDeriveConfig;
+
+use crate::attributes::extract_field_attributes;
+
+// The redacted value of `value`, a reference, eg: `&self.address`.
+// Without `SurrealRedact`, the value has no sensitive field and it is serialized
+pub fn redacted(value: TokenStream) -> TokenStream {
+    quote! {{
+        use surreal_devl::surreal_sensitive::{RedactSensitive as _, RedactSerialized as _};
+        (&surreal_devl::surreal_sensitive::Redacted(#value)).surreal_redacted()
+    }}
+}
+
+pub fn surreal_sensitive_impl(ast: &syn::ItemStruct, config: &SurrealDeriveConfig) -> TokenStream {
+    let struct_name = &ast.ident;
+    let mut fields = vec![];
+
+    for field in ast.fields.iter() {
+        let field_attrs = extract_field_attributes(field);
+        if field_attrs.skip_serializing || field_attrs.record_id {
+            continue;
+        }
+
+        let field_name = field.ident.as_ref().expect("Failed to process variable name, the ident could not be empty");
+        let db_name = field_attrs.db_name(field_name, config);
+
+        if field_attrs.sensitive {
+            fields.push(quote! {
+                map.insert(#db_name.to_owned(), surrealdb::sql::Value::from("***"));
+            });
+        } else {
+            let value = redacted(quote! { &self.#field_name });
+            fields.push(quote! {
+                map.insert(#db_name.to_owned(), #value);
+            });
+        }
+    }
+
+    quote! {
+        impl surreal_devl::surreal_sensitive::SurrealRedact for #struct_name {
+            fn surreal_redacted(&self) -> surrealdb::sql::Value {
+                let mut map: std::collections::BTreeMap<String, surrealdb::sql::Value> = std::collections::BTreeMap::new();
+                #(#fields)*
+                surrealdb::sql::Value::Object(map.into())
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+/// The settings of the generated code, resolved by the macros from the Cargo.toml metadata and the `SURREAL_DERIVE_*` environment
+#[derive(Debug, Clone)]
+pub struct SurrealDeriveConfig {
+    pub use_camel_case: bool,
+    pub enable_log: bool,
+    pub enable_compile_log: bool,
+    pub namespace: String,
+    pub info_log_macro: String,
+}
+
+impl Default for SurrealDeriveConfig {
+    fn default() -> Self {
+        Self {
+            use_camel_case: false,
+            enable_log: false,
+            enable_compile_log: false,
+            namespace: "surreal-ql".to_string(),
+            info_log_macro: "println".to_string(),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+pub mod config;
+pub mod naming_convention;
+pub mod proxy;
+pub mod surreal_edge;
+pub mod surreal_fragment;
+pub mod surreal_id;
+pub mod surreal_qr;
+pub mod surreal_query;
+pub mod surreal_schema;
+pub mod surreal_sensitive;
+pub mod surreal_statement;
+pub mod surreal_table;
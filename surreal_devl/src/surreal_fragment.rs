@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use surrealdb::sql::Value;
+
+/// A part of a statement with the parameters it binds, eg: `age > $p0` with `{ p0: 18 }`.
+/// Returned by `surreal_fragment!` and placed inside other templates by `#frag(...)`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SurrealFragment {
+    pub statement: String,
+    pub params: BTreeMap<String, Value>,
+}
+
+impl SurrealFragment {
+    pub fn new(statement: impl Into<String>, params: BTreeMap<String, Value>) -> Self {
+        Self {
+            statement: statement.into(),
+            params,
+        }
+    }
+
+    /// The statement with every bound `$name` replaced by `param(name, value)`.
+    /// Strings, escaped identifiers and comments are copied as they are, eg: `'$name'` stays unchanged.
+    pub fn map_params(&self, mut param: impl FnMut(&str, &Value) -> String) -> String {
+        let chars = self.statement.chars().collect::<Vec<_>>();
+        let starts_with = |pos: usize, pattern: &str| pattern.chars().enumerate().all(|(i, c)| chars.get(pos + i) == Some(&c));
+        let mut result = String::new();
+        let mut pos = 0;
+
+        while let Some(&c) = chars.get(pos) {
+            let start = pos;
+            match c {
+                '\'' | '"' | '`' | '⟨' => {
+                    let close = if c == '⟨' { '⟩' } else { c };
+                    pos += 1;
+                    while let Some(&c) = chars.get(pos) {
+                        pos += if c == '\\' { 2 } else { 1 };
+                        if c == close {
+                            break;
+                        }
+                    }
+                }
+                '-' if starts_with(pos, "--") => pos = end_of_comment(&chars, pos, "\n"),
+                '/' if starts_with(pos, "//") => pos = end_of_comment(&chars, pos, "\n"),
+                '/' if starts_with(pos, "/*") => pos = end_of_comment(&chars, pos, "*/"),
+                '$' => {
+                    pos += 1;
+                    while chars.get(pos).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+                        pos += 1;
+                    }
+
+                    let name = chars[start + 1..pos].iter().collect::<String>();
+                    if let Some(value) = self.params.get(&name) {
+                        result.push_str(&param(&name, value));
+                        continue;
+                    }
+                }
+                _ => pos += 1,
+            }
+
+            result.extend(&chars[start..pos.min(chars.len())]);
+        }
+
+        result
+    }
+}
+
+/// Name of the next generated parameter, eg: `p2` after `p0` and `p1`.
+/// A name already bound or `reserved` by the statement, eg: a captured `$p1`, is skipped
+pub fn next_param(params: &BTreeMap<String, Value>, reserved: &[&str]) -> String {
+    (params.len()..)
+        .map(|index| format!("p{}", index))
+        .find(|name| !params.contains_key(name) && !reserved.contains(&name.as_str()))
+        .unwrap_or_default()
+}
+
+// Position after a comment starting at `pos`, including the line break or `*/` closing it
+fn end_of_comment(chars: &[char], pos: usize, end: &str) -> usize {
+    let end = end.chars().collect::<Vec<_>>();
+    let mut pos = pos + 2;
+    while pos < chars.len() && !chars[pos..].starts_with(&end) {
+        pos += 1;
+    }
+
+    (pos + end.len()).min(chars.len())
+}
+
+impl From<String> for SurrealFragment {
+    fn from(statement: String) -> Self {
+        Self::new(statement, BTreeMap::new())
+    }
+}
+
+impl From<&str> for SurrealFragment {
+    fn from(statement: &str) -> Self {
+        Self::new(statement, BTreeMap::new())
+    }
+}
+
+impl From<(String, BTreeMap<String, Value>)> for SurrealFragment {
+    fn from((statement, params): (String, BTreeMap<String, Value>)) -> Self {
+        Self::new(statement, params)
+    }
+}
+
+impl From<SurrealFragment> for (String, BTreeMap<String, Value>) {
+    fn from(fragment: SurrealFragment) -> Self {
+        (fragment.statement, fragment.params)
+    }
+}
+
+impl std::fmt::Display for SurrealFragment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.statement)
+    }
+}
@@ -0,0 +1,70 @@
+use std::marker::PhantomData;
+use surrealdb::{Connection, Surreal};
+
+use crate::proxy::default::SurrealDeserializer;
+use crate::surreal_qr::{RPath, SurrealQR, SurrealResponseError};
+
+/// A statement with the type of its result, built by `surreal_query_as!` and run by `execute(&db)`.
+#[must_use]
+pub struct TypedQuery<T> {
+    statement: String,
+    index: usize,
+    span: Option<tracing::Span>,
+    result: PhantomData<T>,
+}
+
+impl<T> TypedQuery<T> {
+    pub fn new(statement: impl Into<String>) -> Self {
+        Self {
+            statement: statement.into(),
+            index: 0,
+            span: None,
+            result: PhantomData,
+        }
+    }
+
+    /// Deserialize the result of the statement at `index` instead of the first one.
+    pub fn statement(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Run the query inside `span`, set by the macros when the runtime log is a `tracing` span.
+    pub fn with_span(mut self, span: tracing::Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.statement
+    }
+}
+
+impl<T> TypedQuery<T>
+where
+    T: SurrealDeserializer,
+{
+    /// Run the query and deserialize the result of the chosen statement,
+    /// any error carries the index and the text of the statement.
+    pub async fn execute<C>(self, db: &Surreal<C>) -> Result<T, SurrealResponseError>
+    where
+        C: Connection,
+    {
+        let result = async {
+            let mut response = db.query(self.statement.as_str()).await?;
+            let result: SurrealQR = response.take(RPath::from(self.index))?;
+            result.deserialize()
+        };
+
+        let span = self.span.clone().unwrap_or_else(tracing::Span::none);
+        tracing::Instrument::instrument(result, span)
+            .await
+            .map_err(|error| SurrealResponseError::StatementFailed(self.index, self.statement.clone(), Box::new(error)))
+    }
+}
+
+impl<T> std::fmt::Display for TypedQuery<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.statement)
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::statements::{DefineIndexStatement, DefineStatement};
+use surrealdb::sql::{Ident, Idiom, Kind, Part, Statement, Value};
+
+/// `(name, type)` of the fields of a type, eg: `("age", "int")`.
+pub type SchemaField = (&'static str, &'static str);
+
+/// `(index, field, unique)` of the indexes of a type, eg: `("email_index", "email", true)`.
+pub type SchemaIndex = (&'static str, &'static str, bool);
+
+fn define_index(table: &Ident, (index, name, unique): &SchemaIndex) -> String {
+    format!("INDEX {} ON TABLE {} FIELDS {}{}", Ident::from(*index), table, Ident::from(*name), if *unique { " UNIQUE" } else { "" })
+}
+
+/// `DEFINE FIELD` and `DEFINE INDEX` statements describing `fields` and `indexes` inside `table`.
+pub fn schema(table: &str, fields: &[SchemaField], indexes: &[SchemaIndex]) -> Vec<String> {
+    let table = Ident::from(table);
+    let fields = fields.iter().map(|(name, kind)| format!("DEFINE FIELD {} ON TABLE {} TYPE {}", Ident::from(*name), table, kind));
+    let indexes = indexes.iter().map(|index| format!("DEFINE {}", define_index(&table, index)));
+
+    fields.chain(indexes).collect()
+}
+
+fn section(info: &Value, name: &str) -> BTreeMap<String, Value> {
+    match info {
+        Value::Object(info) => match info.get(name) {
+            Some(Value::Object(section)) => section.0.clone(),
+            _ => BTreeMap::new(),
+        },
+        _ => BTreeMap::new(),
+    }
+}
+
+fn statement(definition: &Value) -> Option<Statement> {
+    let Value::Strand(definition) = definition else {
+        return None;
+    };
+
+    surrealdb::syn::parse(definition.as_str()).ok()?.0.0.into_iter().next()
+}
+
+fn current_kind(definition: &Value) -> Option<Kind> {
+    match statement(definition)? {
+        Statement::Define(DefineStatement::Field(field)) => Some(field.kind.unwrap_or_default()),
+        _ => None,
+    }
+}
+
+fn index_definition(definition: &Value) -> Option<DefineIndexStatement> {
+    match statement(definition)? {
+        Statement::Define(DefineStatement::Index(index)) => Some(index),
+        _ => None,
+    }
+}
+
+// The keys are escaped idioms, eg: `tags[*]` or `` `my-field` ``, the root is the raw name of the first part
+fn idiom(name: &str) -> Option<Idiom> {
+    surrealdb::syn::idiom(name).ok()
+}
+
+fn root(name: &str) -> Option<String> {
+    match idiom(name)?.0.first() {
+        Some(Part::Field(root)) => Some(root.0.clone()),
+        _ => None,
+    }
+}
+
+/// Compares `info`, the result of `INFO FOR TABLE <table>`, with `fields` and `indexes`,
+/// returns the ordered statements to apply, each flagged `true` when it is destructive.
+pub fn migration(table: &str, fields: &[SchemaField], indexes: &[SchemaIndex], info: &Value) -> Vec<(String, bool)> {
+    let existing_fields = section(info, "fields").into_iter()
+        .filter(|(name, _)| idiom(name).is_some_and(|it| it.0.len() == 1))
+        .filter_map(|(name, definition)| Some((root(&name)?, definition)))
+        .collect::<BTreeMap<_, _>>();
+    let existing_indexes = section(info, "indexes").values().filter_map(index_definition).collect::<Vec<_>>();
+    let table = Ident::from(table);
+    let mut steps = vec![];
+
+    for (name, kind) in fields {
+        match existing_fields.get(*name) {
+            None => steps.push((format!("DEFINE FIELD {} ON TABLE {} TYPE {}", Ident::from(*name), table, kind), false)),
+            Some(definition) => {
+                if current_kind(definition) != surrealdb::syn::kind(kind).ok() {
+                    steps.push((format!("DEFINE FIELD OVERWRITE {} ON TABLE {} TYPE {}", Ident::from(*name), table, kind), true));
+                }
+            }
+        }
+    }
+
+    // A changed index, eg: `unique` toggled, is redefined from the statement of the type
+    for index in indexes {
+        let definition = define_index(&table, index);
+        match existing_indexes.iter().find(|existing| existing.name.0 == index.0) {
+            None => steps.push((format!("DEFINE {}", definition), false)),
+            Some(existing) => {
+                let expected = index_definition(&Value::from(format!("DEFINE {}", definition)));
+                if expected.is_some_and(|expected| expected.cols != existing.cols || expected.index != existing.index) {
+                    steps.push((format!("DEFINE INDEX OVERWRITE {}", definition.trim_start_matches("INDEX ")), true));
+                }
+            }
+        }
+    }
+
+    // Stale indexes go before the fields they may cover
+    for existing in &existing_indexes {
+        if !indexes.iter().any(|(index, _, _)| existing.name.0 == *index) {
+            steps.push((format!("REMOVE INDEX {} ON TABLE {}", existing.name, table), true));
+        }
+    }
+
+    // Nested definitions such as `tags[*]` belong to their root field, children are removed first
+    for name in section(info, "fields").keys().rev() {
+        if !root(name).is_some_and(|root| fields.iter().any(|(field, _)| *field == root)) {
+            let name = idiom(name).map(|it| it.to_string()).unwrap_or_else(|| Ident::from(name.as_str()).to_string());
+            steps.push((format!("REMOVE FIELD {} ON TABLE {}", name, table), true));
+        }
+    }
+
+    steps
+}
@@ -0,0 +1,76 @@
+use surrealdb::sql::Value;
+
+use crate::proxy::default::SurrealSerializer;
+use crate::surreal_edge::Edge;
+use crate::surreal_id::SurrealId;
+
+/// A value with `#[surreal_field(sensitive)]` fields, implemented by `#[derive(SurrealDerive)]` on structs
+/// and by the containers of such values.
+pub trait SurrealRedact {
+    /// The serialized value with `'***'` in place of the sensitive fields, eg: `{ name: 'alice', password: '***' }`.
+    /// The logged statements show this value in place of the record.
+    fn surreal_redacted(&self) -> Value;
+}
+
+impl<T: SurrealRedact + ?Sized> SurrealRedact for &T {
+    fn surreal_redacted(&self) -> Value {
+        (**self).surreal_redacted()
+    }
+}
+
+impl<T: SurrealRedact> SurrealRedact for Box<T> {
+    fn surreal_redacted(&self) -> Value {
+        (**self).surreal_redacted()
+    }
+}
+
+impl<T: SurrealRedact> SurrealRedact for Option<T> {
+    fn surreal_redacted(&self) -> Value {
+        match self {
+            Some(value) => value.surreal_redacted(),
+            None => Value::None,
+        }
+    }
+}
+
+impl<T: SurrealRedact> SurrealRedact for Vec<T> {
+    fn surreal_redacted(&self) -> Value {
+        Value::Array(self.iter().map(SurrealRedact::surreal_redacted).collect::<Vec<_>>().into())
+    }
+}
+
+// Same as its serialization, an edge is written as the id of its record. `#relate(&edge)` redacts the data of the edge
+impl<I, R, O> SurrealRedact for Edge<I, R, O>
+where
+    R: SurrealRedact + SurrealSerializer + SurrealId,
+    I: SurrealId,
+    O: SurrealId,
+{
+    fn surreal_redacted(&self) -> Value {
+        Value::from(self.data.id())
+    }
+}
+
+/// Redacts any value, eg: `(&Redacted(&user.address)).surreal_redacted()`.
+/// The value is redacted by its `SurrealRedact` implementation, otherwise it has no sensitive field and it is serialized
+pub struct Redacted<'a, T: ?Sized>(pub &'a T);
+
+pub trait RedactSensitive {
+    fn surreal_redacted(&self) -> Value;
+}
+
+impl<T: SurrealRedact + ?Sized> RedactSensitive for Redacted<'_, T> {
+    fn surreal_redacted(&self) -> Value {
+        self.0.surreal_redacted()
+    }
+}
+
+pub trait RedactSerialized {
+    fn surreal_redacted(&self) -> Value;
+}
+
+impl<T: SurrealSerializer + Clone> RedactSerialized for &Redacted<'_, T> {
+    fn surreal_redacted(&self) -> Value {
+        self.0.clone().serialize()
+    }
+}
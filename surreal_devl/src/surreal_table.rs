@@ -0,0 +1,43 @@
+use surrealdb::sql::{Ident, Table};
+
+/// A type stored inside a table, implemented by `#[derive(SurrealDerive)]` on structs.
+pub trait SurrealTable {
+    /// Fluent `SELECT` builder of the records.
+    type Select: Default;
+
+    /// Table of the records, given by `#[surreal_derive(table = "...")]` or the snake_case name of the type.
+    const TABLE: &'static str;
+
+    /// Names of the fields read from the database, nested fields are dotted, eg: `address.city`.
+    const FIELDS: &'static [&'static str];
+
+    /// Fields of this type as a `SELECT` projection, eg: `name, age, address.city`.
+    fn surreal_fields() -> String {
+        Self::FIELDS
+            .iter()
+            .map(|field| field.split('.').map(|key| Ident::from(key).to_string()).collect::<Vec<_>>().join("."))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// `SELECT` statement loading this type from its table, eg: `SELECT name, age FROM user`.
+    fn surreal_select() -> String {
+        format!("SELECT {} FROM {}", Self::surreal_fields(), Table::from(Self::TABLE))
+    }
+
+    /// Fluent `SELECT` builder, eg: `User::select().filter(User::fields().age().gt(18)).limit(10)`.
+    fn select() -> Self::Select {
+        Self::Select::default()
+    }
+}
+
+/// Typed paths to the fields stored in the database, implemented by `#[derive(SurrealDerive)]`.
+pub trait SurrealFields {
+    /// Paths below a value of this type, eg: `address.city`.
+    type Fields: Default;
+
+    /// Paths to the fields of this type, to build conditions that follow renames.
+    fn fields() -> Self::Fields {
+        Self::Fields::default()
+    }
+}
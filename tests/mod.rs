@@ -172,7 +172,7 @@ mod test_derive_macro {
     #[test]
     fn test_8_datetime_field_conversion() {
         use chrono::TimeZone;
-        let sample_time = Utc.ymd(2023, 1, 1).and_hms(12, 0, 0);
+        let sample_time = Utc.with_ymd_and_hms(2023, 1, 1, 12, 0, 0).unwrap();
 
         let timed_entity = TimedEntity {
             timestamp: sample_time,
@@ -790,7 +790,7 @@ mod test_in_memory_integration {
             .unwrap()
             .take(RPath::from(0))
             .unwrap();
-        let mut result_1: SurrealQR = db
+        let result_1: SurrealQR = db
             .query(surreal_quote!("SELECT * FROM #id(&user_b) FETCH friend2"))
             .await
             .unwrap()
@@ -1006,7 +1006,7 @@ mod test_edge_relationships {
         db.query(surreal_quote!("CREATE #record(&company2)"))
             .await
             .unwrap();
-        let edge1_created: Option<Edge<Person, Employment, Company>> = db
+        let _edge1_created: Option<Edge<Person, Employment, Company>> = db
             .query(surreal_quote!("#relate(&edge1)"))
             .await
             .unwrap()
@@ -1237,7 +1237,7 @@ mod test_enum_serialization {
             ("Employee", Ok(UserType::Employee)),
             ("Manager", Ok(UserType::Manager)),
             ("Admin", Ok(UserType::Admin)),
-            ("invalid", Err(surreal_devl::surreal_qr::SurrealResponseError::UnknownVariant)),
+            ("invalid", Err(surreal_devl::surreal_qr::SurrealResponseError::UnknownVariant("\"invalid\"".to_string()))),
         ];
 
         for (input, expected) in test_cases {
@@ -1253,7 +1253,7 @@ mod test_enum_serialization {
         let wrong_types = vec![
             Value::from(42),
             Value::from(true),
-            Value::from(3.14),
+            Value::from(2.5),
         ];
 
         for value in wrong_types {
@@ -1287,7 +1287,7 @@ mod test_complex_enum_serialization {
 
     // Nested struct for testing
     #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
-    struct Address {
+    pub struct Address {
         street: String,
         city: String,
         country: String,
@@ -1494,7 +1494,7 @@ mod test_complex_enum_serialization {
             ),
             (
                 Value::from("invalid"),
-                Err(surreal_devl::surreal_qr::SurrealResponseError::UnknownVariant)
+                Err(surreal_devl::surreal_qr::SurrealResponseError::UnknownVariant("\"invalid\"".to_string()))
             ),
         ];
 
@@ -1632,7 +1632,7 @@ mod test_tagged_enum_serialization {
             ),
             (
                 Value::from("invalid"),
-                Err(surreal_devl::surreal_qr::SurrealResponseError::UnknownVariant)
+                Err(surreal_devl::surreal_qr::SurrealResponseError::UnknownVariant("\"invalid\"".to_string()))
             ),
         ];
 
@@ -1672,7 +1672,7 @@ mod test_complex_tagged_enum_serialization {
     }
 
     #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
-    struct Config {
+    pub struct Config {
         enabled: bool,
         settings: Vec<Setting>,
     }
@@ -1872,6 +1872,7 @@ mod test_enum_db_operations {
     // Tagged enum
     #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
     #[surreal_derive(tag = "type")]
+    #[allow(clippy::upper_case_acronyms)]
     pub enum NotificationType {
         Email { address: String },
         SMS { phone: String },
@@ -2043,3 +2044,2399 @@ mod test_enum_db_operations {
         assert_eq!(email_users[0].username, "free_user");
     }
 }
+
+#[cfg(test)]
+mod test_schema_migration {
+    use surreal_derive_plus::SurrealDerive;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    async fn table_info(db: &Surreal<Db>) -> surrealdb::sql::Value {
+        let info: surrealdb::Value = db
+            .query("INFO FOR TABLE account")
+            .await
+            .unwrap()
+            .take(0)
+            .unwrap();
+
+        info.into_inner()
+    }
+
+    // Previous version of the account, still stored in the database
+    #[derive(Clone, Debug, PartialEq, SurrealDerive)]
+    struct LegacyAccount {
+        name: String,
+        age: String,
+        legacy_code: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, SurrealDerive)]
+    struct Account {
+        #[surreal_field(unique)]
+        name: String,
+        age: i32,
+        nickname: Option<String>,
+        tags: Vec<String>,
+        #[surreal_field(db_type = "object")]
+        settings: surrealdb::sql::Value,
+        #[surreal_field(skip_serializing)]
+        cached: bool,
+    }
+
+    #[test]
+    fn test_schema_statements() {
+        assert_eq!(
+            Account::surreal_schema("account"),
+            vec![
+                "DEFINE FIELD name ON TABLE account TYPE string",
+                "DEFINE FIELD age ON TABLE account TYPE int",
+                "DEFINE FIELD nickname ON TABLE account TYPE option<string>",
+                "DEFINE FIELD tags ON TABLE account TYPE array<string>",
+                "DEFINE FIELD settings ON TABLE account TYPE object",
+                "DEFINE INDEX name_index ON TABLE account FIELDS name UNIQUE",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate_live_table() {
+        let db = create_db().await;
+        db.query("DEFINE TABLE account SCHEMAFULL").await.unwrap().check().unwrap();
+        for statement in LegacyAccount::surreal_schema("account") {
+            db.query(statement).await.unwrap().check().unwrap();
+        }
+
+        let steps = Account::surreal_migration("account", &table_info(&db).await);
+        assert_eq!(
+            steps,
+            vec![
+                ("DEFINE FIELD OVERWRITE age ON TABLE account TYPE int".to_owned(), true),
+                ("DEFINE FIELD nickname ON TABLE account TYPE option<string>".to_owned(), false),
+                ("DEFINE FIELD tags ON TABLE account TYPE array<string>".to_owned(), false),
+                ("DEFINE FIELD settings ON TABLE account TYPE object".to_owned(), false),
+                ("DEFINE INDEX name_index ON TABLE account FIELDS name UNIQUE".to_owned(), false),
+                ("REMOVE FIELD legacy_code ON TABLE account".to_owned(), true),
+            ]
+        );
+
+        for (statement, _) in &steps {
+            db.query(statement.as_str()).await.unwrap().check().unwrap();
+        }
+
+        // Once applied the table is in line with the type, nested `tags[*]` included
+        assert!(Account::surreal_migration("account", &table_info(&db).await).is_empty());
+    }
+
+    // Previous version with a plain index on `name` and an escaped field which is indexed
+    #[derive(Clone, Debug, PartialEq, SurrealDerive)]
+    struct IndexedAccount {
+        #[surreal_field(index)]
+        name: String,
+        #[surreal_field(index, name = "legacy-code")]
+        legacy_code: String,
+    }
+
+    #[tokio::test]
+    async fn test_migrate_indexes() {
+        let db = create_db().await;
+        db.query("DEFINE TABLE account SCHEMAFULL").await.unwrap().check().unwrap();
+        for statement in IndexedAccount::surreal_schema("account") {
+            db.query(statement).await.unwrap().check().unwrap();
+        }
+
+        let steps = Account::surreal_migration("account", &table_info(&db).await);
+        assert_eq!(
+            steps,
+            vec![
+                ("DEFINE FIELD age ON TABLE account TYPE int".to_owned(), false),
+                ("DEFINE FIELD nickname ON TABLE account TYPE option<string>".to_owned(), false),
+                ("DEFINE FIELD tags ON TABLE account TYPE array<string>".to_owned(), false),
+                ("DEFINE FIELD settings ON TABLE account TYPE object".to_owned(), false),
+                ("DEFINE INDEX OVERWRITE name_index ON TABLE account FIELDS name UNIQUE".to_owned(), true),
+                ("REMOVE INDEX `legacy-code_index` ON TABLE account".to_owned(), true),
+                ("REMOVE FIELD `legacy-code` ON TABLE account".to_owned(), true),
+            ]
+        );
+
+        for (statement, _) in &steps {
+            db.query(statement.as_str()).await.unwrap().check().unwrap();
+        }
+
+        assert!(Account::surreal_migration("account", &table_info(&db).await).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod test_versioned_records {
+    use std::collections::BTreeMap;
+    use surreal_derive_plus::SurrealDerive;
+    use surreal_devl::proxy::default::{SurrealDeserializer, SurrealSerializer};
+    use surreal_devl::surreal_qr::SurrealResponseError;
+    use surrealdb::sql::{Object, Value};
+
+    // Version 0 had `fullname`, version 1 renamed it to `full_name`, version 2 added `age`
+    fn upgrade_profile(mut object: Object, from_version: u32) -> Result<Object, SurrealResponseError> {
+        match from_version {
+            0 => {
+                let full_name = object.remove("fullname").ok_or(SurrealResponseError::CannotReadNoneValue)?;
+                object.insert("full_name".to_owned(), full_name);
+            }
+            1 => {
+                object.insert("age".to_owned(), Value::from(0));
+            }
+            _ => {}
+        }
+
+        Ok(object)
+    }
+
+    #[derive(Clone, Debug, PartialEq, SurrealDerive)]
+    #[surreal_derive(version = 2, upgrade = "upgrade_profile")]
+    struct Profile {
+        full_name: String,
+        age: i32,
+    }
+
+    #[test]
+    fn test_serialize_writes_version() {
+        let profile = Profile {
+            full_name: "Ada Lovelace".to_owned(),
+            age: 36,
+        };
+
+        let object: Object = profile.into();
+        assert_eq!(object.get("_v"), Some(&Value::from(2)));
+        assert!(Profile::surreal_schema("profile").contains(&"DEFINE FIELD _v ON TABLE profile TYPE int".to_owned()));
+    }
+
+    #[test]
+    fn test_deserialize_upgrades_older_versions() {
+        let mut map = BTreeMap::new();
+        map.insert("fullname".to_owned(), Value::from("Ada Lovelace"));
+        let legacy = Value::Object(Object::from(map));
+
+        let profile: Profile = SurrealDeserializer::deserialize(&legacy).unwrap();
+        assert_eq!(profile, Profile { full_name: "Ada Lovelace".to_owned(), age: 0 });
+
+        let current = Profile { full_name: "Alan Turing".to_owned(), age: 41 };
+        let roundtrip: Profile = SurrealDeserializer::deserialize(&current.clone().serialize()).unwrap();
+        assert_eq!(roundtrip, current);
+    }
+
+    #[test]
+    fn test_failed_upgrade_names_version() {
+        let mut map = BTreeMap::new();
+        map.insert("name".to_owned(), Value::from("Ada Lovelace"));
+        let broken = Value::Object(Object::from(map));
+
+        let result: Result<Profile, SurrealResponseError> = SurrealDeserializer::deserialize(&broken);
+        assert_eq!(
+            result,
+            Err(SurrealResponseError::ParsingFieldFailed(
+                "_v: upgrade from version 0".to_owned(),
+                Box::new(SurrealResponseError::CannotReadNoneValue)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_unknown_version_is_rejected() {
+        for (version, message) in [(-1, "_v: -1 is not a version between 0 and 2"), (3, "_v: 3 is not a version between 0 and 2")] {
+            let mut map = BTreeMap::new();
+            map.insert("full_name".to_owned(), Value::from("Ada Lovelace"));
+            map.insert("age".to_owned(), Value::from(36));
+            map.insert("_v".to_owned(), Value::from(version));
+
+            let result: Result<Profile, SurrealResponseError> = SurrealDeserializer::deserialize(&Value::Object(Object::from(map)));
+            assert_eq!(result, Err(SurrealResponseError::OutOfRange(message.to_owned())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_derive_surreal_id {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_id::{Link, SurrealId};
+    use surrealdb::sql::{Id, Thing, Value};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(table = "user")]
+    struct Member {
+        #[surreal_field(id)]
+        name: String,
+        age: i32,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct InvoiceLine {
+        #[surreal_field(id)]
+        number: i64,
+        amount: f64,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(table = "membership")]
+    struct Membership {
+        #[surreal_field(id)]
+        member: Link<Member>,
+        #[surreal_field(id)]
+        year: i32,
+        active: bool,
+    }
+
+    #[test]
+    fn test_single_string_id() {
+        let member = Member {
+            name: "alice".to_owned(),
+            age: 30,
+        };
+
+        assert_eq!(member.id(), Thing::from(("user", "alice")));
+        assert_eq!(
+            surreal_quote!("CREATE #record(&member)"),
+            "CREATE user:alice CONTENT { age: 30, name: 'alice' }"
+        );
+    }
+
+    #[test]
+    fn test_number_id_with_default_table() {
+        let line = InvoiceLine {
+            number: 42,
+            amount: 9.5,
+        };
+
+        assert_eq!(line.id(), Thing::from(("invoice_line", Id::Number(42))));
+        assert_eq!(surreal_quote!("SELECT * FROM #id(&line)"), "SELECT * FROM invoice_line:42");
+    }
+
+    #[test]
+    fn test_composite_id() {
+        let membership = Membership {
+            member: Link::Id(Thing::from(("user", "alice"))),
+            year: 2024,
+            active: true,
+        };
+
+        assert_eq!(
+            membership.id(),
+            Thing::from((
+                "membership",
+                Id::Array(vec![Value::from(Thing::from(("user", "alice"))), Value::from(2024)].into())
+            ))
+        );
+        assert_eq!(surreal_quote!("#id(&membership)"), "membership:[user:alice, 2024]");
+    }
+}
+
+#[cfg(test)]
+mod test_record_id_field {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_id::SurrealId;
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::sql::Thing;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Note {
+        #[surreal_field(record_id)]
+        id: Option<Thing>,
+        #[surreal_field(id)]
+        slug: String,
+        text: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Label {
+        #[surreal_field(record_id)]
+        key: Thing,
+        color: String,
+    }
+
+    #[test]
+    fn test_record_id_left_out_of_content() {
+        let label = Label {
+            key: Thing::from(("label", "urgent")),
+            color: "red".to_owned(),
+        };
+
+        assert_eq!(label.id(), Thing::from(("label", "urgent")));
+        assert_eq!(surreal_quote!("CREATE #record(&label)"), "CREATE label:urgent CONTENT { color: 'red' }");
+        assert_eq!(
+            Label::surreal_schema("label"),
+            vec!["DEFINE FIELD color ON TABLE label TYPE string".to_owned()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_id_roundtrip() {
+        let db = create_db().await;
+
+        let note = Note {
+            id: None,
+            slug: "groceries".to_owned(),
+            text: "Milk".to_owned(),
+        };
+
+        // Without a record id the id fields are used
+        assert_eq!(note.id(), Thing::from(("note", "groceries")));
+        db.query(surreal_quote!("CREATE #record(&note)")).await.unwrap().check().unwrap();
+
+        let stored: Option<Note> = db
+            .query("SELECT * FROM note")
+            .await
+            .unwrap()
+            .take(RPath::from(0))
+            .unwrap();
+        let mut stored = stored.unwrap();
+        assert_eq!(stored.id, Some(Thing::from(("note", "groceries"))));
+
+        // The received id is used, even when the id fields changed
+        stored.slug = "shopping".to_owned();
+        stored.text = "Milk and eggs".to_owned();
+        db.query(surreal_quote!("UPDATE #id(&stored) #content(&stored)")).await.unwrap().check().unwrap();
+
+        let updated: Option<Note> = db
+            .query("SELECT * FROM note:groceries")
+            .await
+            .unwrap()
+            .take(RPath::from(0))
+            .unwrap();
+        assert_eq!(updated.unwrap().text, "Milk and eggs");
+    }
+}
+
+#[cfg(test)]
+mod test_id_strategies {
+    use chrono::{DateTime, Utc};
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_id::SurrealId;
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::sql::{Id, Thing, Value};
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(table = "event", id = "ulid")]
+    struct Event {
+        #[surreal_field(record_id)]
+        id: Option<Thing>,
+        name: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(id = "uuid", pregenerate_id)]
+    struct Session {
+        #[surreal_field(record_id)]
+        id: Thing,
+        user: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Device {
+        #[surreal_field(id)]
+        serial: surrealdb::Uuid,
+        model: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Temperature {
+        #[surreal_field(id)]
+        city: String,
+        #[surreal_field(id)]
+        at: DateTime<Utc>,
+        celsius: f64,
+    }
+
+    #[test]
+    fn test_generated_by_database() {
+        let event = Event {
+            id: None,
+            name: "launch".to_owned(),
+        };
+
+        assert_eq!(surreal_quote!("CREATE #record(&event)"), "CREATE event:ulid() CONTENT { name: 'launch' }");
+    }
+
+    #[tokio::test]
+    async fn test_records_without_id() {
+        let db = create_db().await;
+        let events = vec![
+            Event { id: None, name: "launch".to_owned() },
+            Event { id: None, name: "landing".to_owned() },
+        ];
+
+        let inserted: Vec<Event> = db.query(surreal_quote!("#insert(&events)")).await.unwrap().take(RPath::from(0)).unwrap();
+        assert_eq!(inserted.len(), 2);
+        assert!(inserted.iter().all(|event| matches!(event.id.as_ref().map(|id| &id.id), Some(Id::String(_)))));
+    }
+
+    #[test]
+    fn test_pregenerated_in_rust() {
+        let session = Session {
+            id: Session::generate_id(),
+            user: "alice".to_owned(),
+        };
+
+        let id = session.id();
+        assert_eq!(id.tb, "session");
+        assert!(matches!(id.id, Id::Uuid(_)));
+        assert_eq!(session.id(), id);
+        assert_eq!(surreal_quote!("CREATE #record(&session)"), format!("CREATE {} CONTENT {{ user: 'alice' }}", id));
+        assert_ne!(Session::generate_id(), id);
+    }
+
+    #[tokio::test]
+    async fn test_pregenerated_id_is_stored() {
+        let db = create_db().await;
+        let session = Session {
+            id: Session::generate_id(),
+            user: "alice".to_owned(),
+        };
+
+        let created: Option<Session> = db.query(surreal_quote!("CREATE #record(&session)")).await.unwrap().take(RPath::from(0)).unwrap();
+        assert_eq!(created, Some(session));
+    }
+
+    #[test]
+    fn test_typed_keys() {
+        let serial = surrealdb::Uuid::new_v4();
+        let device = Device {
+            serial,
+            model: "sensor".to_owned(),
+        };
+
+        assert_eq!(device.id(), Thing::from(("device", Id::Uuid(serial.into()))));
+        assert_eq!(
+            Temperature::id_range(vec![Value::from("London"), Value::None]..=vec![Value::from("London"), Value::from(i64::MAX)]).to_string(),
+            "temperature:['London', NONE]..=['London', 9223372036854775807]"
+        );
+
+        // Uuid keys are uuids as in the id of the record
+        let range = Device::id_range(serial..);
+        let Id::Range(range) = range.id else {
+            panic!("Expected a range, got {}", range);
+        };
+        assert_eq!(range.beg, std::ops::Bound::Included(Id::Uuid(serial.into())));
+    }
+
+    #[tokio::test]
+    async fn test_generated_id_is_received() {
+        let db = create_db().await;
+
+        let event = Event {
+            id: None,
+            name: "launch".to_owned(),
+        };
+
+        let created: Option<Event> = db
+            .query(surreal_quote!("CREATE #record(&event)"))
+            .await
+            .unwrap()
+            .take(RPath::from(0))
+            .unwrap();
+        let created = created.unwrap();
+        let id = created.id.clone().unwrap();
+        assert_eq!(id.tb, "event");
+
+        // Once received, the id is used as is
+        assert_eq!(surreal_quote!("#id(&created)"), id.to_string());
+    }
+}
+
+#[cfg(test)]
+mod test_bulk_insert {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Account {
+        #[surreal_field(id)]
+        name: String,
+        age: i64,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Visit {
+        page: String,
+    }
+
+    fn accounts() -> Vec<Account> {
+        vec![
+            Account { name: "alice".to_owned(), age: 30 },
+            Account { name: "bob".to_owned(), age: 25 },
+            Account { name: "carol".to_owned(), age: 41 },
+        ]
+    }
+
+    #[test]
+    fn test_insert_with_ids() {
+        let accounts = accounts();
+
+        assert_eq!(
+            surreal_quote!("#insert(&accounts)"),
+            "INSERT INTO account [{ age: 30, id: account:alice, name: 'alice' }, { age: 25, id: account:bob, name: 'bob' }, { age: 41, id: account:carol, name: 'carol' }]"
+        );
+    }
+
+    #[test]
+    fn test_insert_into_table() {
+        let visits = [Visit { page: "home".to_owned() }, Visit { page: "about".to_owned() }];
+
+        assert_eq!(
+            surreal_quote!("#insert(\"visit\", visits.iter())"),
+            "INSERT INTO visit [{ page: 'home' }, { page: 'about' }]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_insert_nothing() {
+        let db = create_db().await;
+        let visits: Vec<Visit> = vec![];
+        let accounts: Vec<Account> = vec![];
+
+        assert_eq!(surreal_quote!("#insert(\"visit\", &visits)"), "INSERT INTO visit []");
+        assert_eq!(surreal_quote!("#insert(&accounts, chunk = 2)"), "INSERT []");
+
+        let mut response = db.query(surreal_quote!("#insert(\"visit\", &visits); #insert(&accounts); RETURN 1")).await.unwrap();
+        let inserted: Vec<Visit> = response.take(RPath::from(0)).unwrap();
+        assert!(inserted.is_empty());
+        let inserted: Vec<Account> = response.take(RPath::from(1)).unwrap();
+        assert!(inserted.is_empty());
+        let last: Option<i64> = response.take(RPath::from(2)).unwrap();
+        assert_eq!(last, Some(1));
+    }
+
+    #[test]
+    fn test_insert_in_chunks() {
+        let accounts = accounts();
+
+        assert_eq!(
+            surreal_quote!("#insert(&accounts, chunk = 2);"),
+            "INSERT INTO account [{ age: 30, id: account:alice, name: 'alice' }, { age: 25, id: account:bob, name: 'bob' }]; INSERT INTO account [{ age: 41, id: account:carol, name: 'carol' }];"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_insert_records() {
+        let db = create_db().await;
+        let accounts = accounts();
+
+        db.query(surreal_quote!("#insert(&accounts, chunk = 2)")).await.unwrap().check().unwrap();
+
+        let stored: Vec<Account> = db
+            .query("SELECT * FROM account ORDER BY name")
+            .await
+            .unwrap()
+            .take(RPath::from(0))
+            .unwrap();
+        assert_eq!(stored, accounts);
+    }
+}
+
+#[cfg(test)]
+mod test_upsert_merge {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Profile {
+        #[surreal_field(id)]
+        name: String,
+        bio: Option<String>,
+        #[surreal_field(skip_serializing, default)]
+        score: i64,
+    }
+
+    #[test]
+    fn test_upsert() {
+        let profile = Profile {
+            name: "alice".to_owned(),
+            bio: Some("hello".to_owned()),
+            score: 0,
+        };
+
+        assert_eq!(
+            surreal_quote!("#upsert(&profile)"),
+            "UPSERT profile:alice CONTENT { bio: 'hello', name: 'alice' }"
+        );
+    }
+
+    #[test]
+    fn test_merge_drops_none_fields() {
+        let profile = Profile {
+            name: "alice".to_owned(),
+            bio: None,
+            score: 10,
+        };
+
+        assert_eq!(
+            surreal_quote!("UPDATE #id(&profile) #merge(&profile)"),
+            "UPDATE profile:alice MERGE { name: 'alice' }"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_keeps_other_fields() {
+        let db = create_db().await;
+        db.query("CREATE profile:alice CONTENT { name: 'alice', bio: 'hello', score: 7 }").await.unwrap().check().unwrap();
+
+        let profile = Profile {
+            name: "alice".to_owned(),
+            bio: None,
+            score: 0,
+        };
+        db.query(surreal_quote!("UPDATE #id(&profile) #merge(&profile)")).await.unwrap().check().unwrap();
+
+        let score: Option<i64> = db.query("SELECT VALUE score FROM ONLY profile:alice").await.unwrap().take(0).unwrap();
+        let bio: Option<String> = db.query("SELECT VALUE bio FROM ONLY profile:alice").await.unwrap().take(0).unwrap();
+        assert_eq!(score, Some(7));
+        assert_eq!(bio, Some("hello".to_owned()));
+
+        db.query(surreal_quote!("#upsert(&profile)")).await.unwrap().check().unwrap();
+        let stored: Option<Profile> = db.query("SELECT * FROM ONLY profile:alice").await.unwrap().take(RPath::from(0)).unwrap();
+        assert_eq!(stored, Some(profile));
+    }
+}
+
+#[cfg(test)]
+mod test_set_clause {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Address {
+        city: String,
+        zip: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Member {
+        #[surreal_field(name = "display_name")]
+        name: String,
+        age: i64,
+        address: Address,
+    }
+
+    fn member() -> Member {
+        Member {
+            name: "alice".to_owned(),
+            age: 3,
+            address: Address {
+                city: "Paris".to_owned(),
+                zip: "75001".to_owned(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_set() {
+        let member = member();
+
+        assert_eq!(
+            surreal_quote!("UPDATE member:alice #set(&member)"),
+            "UPDATE member:alice SET address.city = 'Paris', address.zip = '75001', age = 3, display_name = 'alice'"
+        );
+    }
+
+    #[test]
+    fn test_set_include_exclude() {
+        let member = member();
+
+        assert_eq!(
+            surreal_quote!("UPDATE member:alice #set(&member, include = [age, address.city])"),
+            "UPDATE member:alice SET address.city = 'Paris', age = 3"
+        );
+        assert_eq!(
+            surreal_quote!("UPDATE member:alice #set(&member, exclude = [\"address\", display_name])"),
+            "UPDATE member:alice SET age = 3"
+        );
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Empty {}
+
+    #[tokio::test]
+    async fn test_set_nothing() {
+        let db = create_db().await;
+        db.query("CREATE member:alice CONTENT { display_name: 'alice', age: 1 }").await.unwrap().check().unwrap();
+
+        let member = member();
+        let empty = Empty {};
+        assert_eq!(surreal_quote!("UPDATE member:alice #set(&member, exclude = [display_name, age, address])"), "UPDATE member:alice ");
+        assert_eq!(surreal_quote!("UPDATE member:alice #set(&member, include = [nickname])"), "UPDATE member:alice ");
+        assert_eq!(surreal_quote!("UPDATE member:alice #set(&empty)"), "UPDATE member:alice ");
+
+        db.query(surreal_quote!("UPDATE member:alice #set(&empty)")).await.unwrap().check().unwrap();
+        let age: Option<i64> = db.query("SELECT VALUE age FROM ONLY member:alice").await.unwrap().take(0).unwrap();
+        assert_eq!(age, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_set_keeps_other_fields() {
+        let db = create_db().await;
+        db.query("CREATE member:alice CONTENT { display_name: 'alice', age: 1, address: { city: 'Lyon', zip: '69001', country: 'FR' } }").await.unwrap().check().unwrap();
+
+        let member = member();
+        db.query(surreal_quote!("UPDATE member:alice #set(&member, exclude = [age])")).await.unwrap().check().unwrap();
+
+        let city: Option<String> = db.query("SELECT VALUE address.city FROM ONLY member:alice").await.unwrap().take(0).unwrap();
+        let country: Option<String> = db.query("SELECT VALUE address.country FROM ONLY member:alice").await.unwrap().take(0).unwrap();
+        let age: Option<i64> = db.query("SELECT VALUE age FROM ONLY member:alice").await.unwrap().take(0).unwrap();
+        assert_eq!(city, Some("Paris".to_owned()));
+        assert_eq!(country, Some("FR".to_owned()));
+        assert_eq!(age, Some(1));
+    }
+}
+
+#[cfg(test)]
+mod test_projection {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_table::SurrealTable;
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::sql::Thing;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(table = "user")]
+    struct UserCard {
+        #[surreal_field(record_id)]
+        id: Thing,
+        name: String,
+        age: i64,
+        #[surreal_field(name = "address.city")]
+        city: String,
+        #[surreal_field(skip_deserializing)]
+        selected: bool,
+    }
+
+    #[test]
+    fn test_fields() {
+        assert_eq!(UserCard::FIELDS, &["id", "name", "age", "address.city"]);
+        assert_eq!(surreal_quote!("SELECT #fields(UserCard) FROM user"), "SELECT id, name, age, address.city FROM user");
+        assert_eq!(UserCard::surreal_select(), "SELECT id, name, age, address.city FROM user");
+    }
+
+    #[tokio::test]
+    async fn test_load_projection() {
+        let db = create_db().await;
+        db.query("CREATE user:alice CONTENT { name: 'alice', age: 30, password: 'secret', address: { city: 'Paris', zip: '75001' } }").await.unwrap().check().unwrap();
+
+        let cards: Vec<UserCard> = db.query(UserCard::surreal_select()).await.unwrap().take(RPath::from(0)).unwrap();
+        assert_eq!(cards, vec![UserCard {
+            id: Thing::from(("user", "alice")),
+            name: "alice".to_owned(),
+            age: 30,
+            city: "Paris".to_owned(),
+            selected: false,
+        }]);
+    }
+}
+
+#[cfg(test)]
+mod test_table_name {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_table::SurrealTable;
+    use surreal_devl::surreal_id::SurrealId;
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(table = "app_user")]
+    struct AppUser {
+        #[surreal_field(id)]
+        name: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct BlogPost {
+        title: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(table = "audit-log")]
+    struct AuditLog {
+        action: String,
+    }
+
+    #[test]
+    fn test_table_constant() {
+        assert_eq!(AppUser::TABLE, "app_user");
+        assert_eq!(BlogPost::TABLE, "blog_post");
+
+        let user = AppUser { name: "alice".to_owned() };
+        assert_eq!(user.id().tb, AppUser::TABLE);
+    }
+
+    #[test]
+    fn test_table_placeholder() {
+        assert_eq!(surreal_quote!("SELECT * FROM #table(AppUser)"), "SELECT * FROM app_user");
+        assert_eq!(surreal_quote!("SELECT * FROM #table(AuditLog)"), "SELECT * FROM `audit-log`");
+    }
+}
+
+#[cfg(test)]
+mod test_field_paths {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_table::{SurrealFields, SurrealTable};
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::sql::{Idiom, Thing};
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Address {
+        city: String,
+        #[surreal_field(name = "postal-code")]
+        zip: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(tag = "type")]
+    enum Contact {
+        Email { address: String },
+        Phone(String),
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    enum Plan {
+        Free,
+        Paid { level: i32 },
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(id = "rand")]
+    struct Customer {
+        #[surreal_field(record_id)]
+        id: Option<Thing>,
+        #[surreal_field(name = "display_name")]
+        name: String,
+        #[surreal_field(nested)]
+        address: Address,
+        #[surreal_field(nested)]
+        previous_addresses: Vec<Address>,
+        #[surreal_field(nested)]
+        contact: Contact,
+        #[surreal_field(nested)]
+        plan: Plan,
+        #[surreal_field(skip_serializing, default)]
+        cached: bool,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Order {
+        total: i64,
+    }
+
+    // The names once taken by the derive belong to the module
+    struct OrderFields;
+    struct OrderSelect;
+
+    impl Order {
+        const TABLE: &'static str = "legacy_orders";
+
+        fn select() -> OrderSelect {
+            OrderSelect
+        }
+    }
+
+    #[test]
+    fn test_generated_items_do_not_collide() {
+        let (OrderFields, OrderSelect) = (OrderFields, Order::select());
+        assert_eq!(Order::TABLE, "legacy_orders");
+        assert_eq!(<Order as SurrealTable>::TABLE, "order");
+        assert_eq!(<Order as SurrealTable>::select().to_string(), "SELECT * FROM order");
+        assert_eq!(<Order as SurrealFields>::fields().total().to_string(), "total");
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(Customer::fields().id().to_string(), "id");
+        assert_eq!(Customer::fields().name().to_string(), "display_name");
+        assert_eq!(Customer::fields().address().to_string(), "address");
+        assert_eq!(Customer::fields().address().city().to_string(), "address.city");
+        assert_eq!(Customer::fields().address().zip().to_string(), "address.`postal-code`");
+        assert_eq!(Customer::fields().previous_addresses().city().to_string(), "previous_addresses.city");
+        assert_eq!(Customer::fields().contact().tag().to_string(), "contact.type");
+        assert_eq!(Customer::fields().contact().email().address().to_string(), "contact.`value`.address");
+        assert_eq!(Customer::fields().contact().phone().to_string(), "contact.`value`");
+        assert_eq!(Customer::fields().plan().paid().level().to_string(), "plan.paid.level");
+        assert_eq!(Idiom::from(Customer::fields().address()), Idiom::from("address"));
+    }
+
+    #[test]
+    fn test_paths_in_placeholders() {
+        assert_eq!(
+            surreal_quote!("SELECT * FROM customer WHERE #(Customer::fields().address().city()) = 'Paris'"),
+            "SELECT * FROM customer WHERE address.city = 'Paris'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filter_with_paths() {
+        let db = create_db().await;
+        let customer = |name: &str, city: &str, contact: Contact| Customer {
+            id: Some(Thing::from(("customer", name))),
+            name: name.to_owned(),
+            address: Address { city: city.to_owned(), zip: "00000".to_owned() },
+            previous_addresses: vec![],
+            contact,
+            plan: Plan::Free,
+            cached: false,
+        };
+
+        let alice = customer("alice", "Paris", Contact::Email { address: "alice@example.com".to_owned() });
+        let bob = customer("bob", "Lyon", Contact::Phone("0600000000".to_owned()));
+        db.query(surreal_quote!("#upsert(&alice); #upsert(&bob)")).await.unwrap().check().unwrap();
+
+        let city = "Paris".to_owned();
+        let in_paris: Vec<Customer> = db
+            .query(surreal_quote!("SELECT * FROM customer WHERE #(Customer::fields().address().city()) = #val(&city)"))
+            .await
+            .unwrap()
+            .take(RPath::from(0))
+            .unwrap();
+        assert_eq!(in_paris, vec![alice]);
+
+        let by_phone: Vec<Customer> = db
+            .query(surreal_quote!("SELECT * FROM customer WHERE #(Customer::fields().contact().tag()) = 'Phone'"))
+            .await
+            .unwrap()
+            .take(RPath::from(0))
+            .unwrap();
+        assert_eq!(by_phone, vec![bob]);
+    }
+}
+
+#[cfg(test)]
+mod test_select_builder {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_table::{SurrealFields, SurrealTable};
+    use surrealdb::sql::Thing;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(table = "member")]
+    struct Member {
+        #[surreal_field(id)]
+        name: String,
+        age: i64,
+        #[surreal_field(name = "team_id")]
+        team: Option<Thing>,
+    }
+
+    fn member(name: &str, age: i64) -> Member {
+        Member {
+            name: name.to_owned(),
+            age,
+            team: None,
+        }
+    }
+
+    #[test]
+    fn test_statement() {
+        let adults = Member::select()
+            .filter(Member::fields().age().gt(18))
+            .filter(Member::fields().name().ne("bob".to_owned()))
+            .order_by_desc(Member::fields().age())
+            .order_by(Member::fields().name())
+            .limit(10)
+            .start(5)
+            .fetch_related(Member::fields().team());
+
+        assert_eq!(
+            adults.to_string(),
+            "SELECT * FROM member WHERE age > 18 AND name != 'bob' ORDER BY age DESC, name ASC LIMIT 10 START 5 FETCH team_id"
+        );
+
+        let min = 18;
+        assert_eq!(
+            Member::select().filter(Member::fields().age().gt(min)).to_string(),
+            surreal_quote!("SELECT * FROM #table(Member) WHERE #(Member::fields().age()) > #min")
+        );
+    }
+
+    #[test]
+    fn test_filter_any() {
+        let statement = Member::select()
+            .filter(Member::fields().name().ne("bob".to_owned()))
+            .filter_any([Member::fields().age().lt(18), Member::fields().age().gte(65)]);
+
+        assert_eq!(statement.to_string(), "SELECT * FROM member WHERE name != 'bob' AND (age < 18 OR age >= 65)");
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let db = create_db().await;
+        let members = vec![member("alice", 30), member("bob", 12), member("carol", 70)];
+        db.query(surreal_quote!("#insert(&members)")).await.unwrap().check().unwrap();
+
+        let adults = Member::select()
+            .filter(Member::fields().age().gte(18))
+            .order_by(Member::fields().age())
+            .fetch(&db)
+            .await
+            .unwrap();
+        assert_eq!(adults, vec![member("alice", 30), member("carol", 70)]);
+
+        let named = Member::select()
+            .filter(Member::fields().name().inside(vec!["bob".to_owned(), "carol".to_owned()]))
+            .order_by_desc(Member::fields().name())
+            .limit(1)
+            .fetch(&db)
+            .await
+            .unwrap();
+        assert_eq!(named, vec![member("carol", 70)]);
+    }
+}
+
+#[cfg(test)]
+mod test_template_control_flow {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Player {
+        #[surreal_field(id)]
+        name: String,
+        age: i64,
+    }
+
+    fn search(min: Option<i64>, names: &[String]) -> String {
+        surreal_quote!("
+            SELECT * FROM player WHERE true
+            #if(let Some(min) = min){ AND age >= #min }
+            #if(!names.is_empty()){ AND name IN [#for(name in names){#val(name)}#sep(\", \")] }
+            ORDER BY name
+        ")
+    }
+
+    #[test]
+    fn test_if() {
+        assert_eq!(search(None, &[]), "SELECT * FROM player WHERE true ORDER BY name");
+        assert_eq!(search(Some(18), &[]), "SELECT * FROM player WHERE true AND age >= 18 ORDER BY name");
+
+        let admin = false;
+        assert_eq!(surreal_quote!("SELECT * FROM #if(admin){ account }#else{ player }"), "SELECT * FROM player");
+        assert_eq!(surreal_quote!("#if(admin){ DELETE player }"), "");
+    }
+
+    #[test]
+    fn test_for() {
+        let names = vec!["alice".to_owned(), "bob".to_owned()];
+        assert_eq!(search(None, &names), "SELECT * FROM player WHERE true AND name IN ['alice', 'bob'] ORDER BY name");
+
+        let ages = [1, 2, 3];
+        assert_eq!(surreal_quote!("RETURN [#for(age in ages){ #age }#sep(\",\")]"), "RETURN [1,2,3]");
+        assert_eq!(surreal_quote!("#for(age in ages.iter()){CREATE player SET age = #age;}"), "CREATE player SET age = 1;CREATE player SET age = 2;CREATE player SET age = 3;");
+    }
+
+    #[test]
+    fn test_nesting() {
+        let groups = vec![vec![1, 2], vec![], vec![3]];
+        assert_eq!(
+            surreal_quote!("RETURN [#for(group in &groups){ #if(group.is_empty()){ NONE }#else{ [#for(value in group){#value}#sep(\", \")] } }#sep(\", \")]"),
+            "RETURN [[1, 2], NONE, [3]]"
+        );
+    }
+
+    #[test]
+    fn test_generated_variables_are_hidden() {
+        let statement = "player";
+        let index = 2;
+        let space = 3;
+        let items = [1];
+        assert_eq!(
+            surreal_quote!("SELECT * FROM #statement #for(item in items){ LIMIT #index START #space #item }"),
+            "SELECT * FROM player LIMIT 2 START 3 1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let db = create_db().await;
+        let players = vec![
+            Player { name: "alice".to_owned(), age: 30 },
+            Player { name: "bob".to_owned(), age: 12 },
+            Player { name: "carol".to_owned(), age: 70 },
+        ];
+        db.query(surreal_quote!("#insert(&players)")).await.unwrap().check().unwrap();
+
+        let found: Vec<Player> = db
+            .query(search(Some(18), &["alice".to_owned(), "bob".to_owned()]))
+            .await
+            .unwrap()
+            .take(RPath::from(0))
+            .unwrap();
+        assert_eq!(found, vec![players[0].clone()]);
+    }
+}
+
+#[cfg(test)]
+mod test_fragments {
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use surreal_derive_plus::{surreal_fragment, surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_fragment::SurrealFragment;
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::sql::Value;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Player {
+        #[surreal_field(id)]
+        name: String,
+        age: i64,
+    }
+
+    fn adults(excluded: String) -> SurrealFragment {
+        let min = 18;
+        surreal_fragment!("age >= #min AND name != #val(&excluded)")
+    }
+
+    #[test]
+    fn test_fragment() {
+        let SurrealFragment { statement, params } = adults("bob".to_owned());
+        assert_eq!(statement, "age >= $p0 AND name != $p1");
+        assert_eq!(params, BTreeMap::from([("p0".to_owned(), Value::from(18)), ("p1".to_owned(), Value::from("bob"))]));
+    }
+
+    #[test]
+    fn test_fragment_inlined() {
+        let adults = adults("bob".to_owned());
+        let ordered = surreal_quote!("ORDER BY age");
+
+        assert_eq!(
+            surreal_quote!("SELECT * FROM player WHERE #frag(adults) #frag(ordered)"),
+            "SELECT * FROM player WHERE age >= 18 AND name != 'bob' ORDER BY age"
+        );
+    }
+
+    #[test]
+    fn test_fragment_literals_are_kept() {
+        let name = "bob".to_owned();
+        let fragment = surreal_fragment!("name = #val(&name) OR note = '$p0' -- $p0");
+        assert_eq!(fragment.statement, "name = $p0 OR note = '$p0' -- $p0");
+
+        assert_eq!(
+            surreal_quote!("SELECT * FROM player WHERE #frag(fragment)"),
+            "SELECT * FROM player WHERE name = 'bob' OR note = '$p0' -- $p0"
+        );
+        let SurrealFragment { statement, params } = surreal_fragment!("RETURN #val(&1); SELECT * FROM player WHERE #frag(fragment)");
+        assert_eq!(statement, "RETURN $p0; SELECT * FROM player WHERE name = $p1 OR note = '$p0' -- $p0");
+        assert_eq!(params["p1"], Value::from("bob"));
+    }
+
+    #[test]
+    fn test_fragments_are_renumbered() {
+        let max = 65;
+        let adults = adults("bob".to_owned());
+        let SurrealFragment { statement, params } = surreal_fragment!("SELECT * FROM player WHERE age < #max AND #frag(adults) AND #frag(adults)");
+
+        assert_eq!(statement, "SELECT * FROM player WHERE age < $p0 AND age >= $p1 AND name != $p2 AND age >= $p3 AND name != $p4");
+        assert_eq!(params.len(), 5);
+        assert_eq!(params["p0"], Value::from(65));
+        assert_eq!(params["p4"], Value::from("bob"));
+
+        // `$p1` is never confused with `$p10`
+        let ages: Vec<i64> = (0..11).collect();
+        let many = surreal_fragment!("[#for(age in &ages){#age}#sep(\", \")]");
+        let SurrealFragment { statement, params } = surreal_fragment!("RETURN [#max, #frag(many)]");
+        assert_eq!(statement, "RETURN [$p0, [$p1, $p2, $p3, $p4, $p5, $p6, $p7, $p8, $p9, $p10, $p11]]");
+        assert_eq!(params["p2"], Value::from(1));
+        assert_eq!(params["p11"], Value::from(10));
+    }
+
+    #[tokio::test]
+    async fn test_bound_query() {
+        let db = create_db().await;
+        let players = vec![
+            Player { name: "alice".to_owned(), age: 30 },
+            Player { name: "bob".to_owned(), age: 40 },
+            Player { name: "carol".to_owned(), age: 12 },
+        ];
+        db.query(surreal_quote!("#insert(&players)")).await.unwrap().check().unwrap();
+
+        let adults = adults("bob".to_owned());
+        let SurrealFragment { statement, params } = surreal_fragment!("SELECT * FROM #table(Player) WHERE #frag(adults)");
+        let found: Vec<Player> = db.query(statement).bind(params).await.unwrap().take(RPath::from(0)).unwrap();
+        assert_eq!(found, vec![players[0].clone()]);
+    }
+}
+
+#[cfg(test)]
+mod test_template_lexer {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_fragment, surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_fragment::SurrealFragment;
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Note {
+        #[surreal_field(id)]
+        name: String,
+        text: String,
+    }
+
+    #[test]
+    fn test_string_literals_are_kept() {
+        let name = "alice".to_owned();
+        assert_eq!(
+            surreal_quote!("SELECT * FROM note WHERE text = 'a   #name\n  b' AND name = #val(&name)"),
+            "SELECT * FROM note WHERE text = 'a   #name\n  b' AND name = 'alice'"
+        );
+        assert_eq!(
+            surreal_quote!(r#"RETURN ["it\"s  #name", 'it\'s  #name', #val(&name)]"#),
+            r#"RETURN ["it\"s  #name", 'it\'s  #name', 'alice']"#
+        );
+    }
+
+    #[test]
+    fn test_braces_inside_literals() {
+        let flag = true;
+        assert_eq!(
+            surreal_quote!("RETURN #if(flag){ '}  {' }"),
+            "RETURN '}  {'"
+        );
+    }
+
+    #[test]
+    fn test_escaped_identifiers_are_kept() {
+        let name = "alice".to_owned();
+        assert_eq!(
+            surreal_quote!("SELECT `first  #name`, ⟨last  #name⟩ FROM note WHERE name = #val(&name)"),
+            "SELECT `first  #name`, ⟨last  #name⟩ FROM note WHERE name = 'alice'"
+        );
+    }
+
+    #[test]
+    fn test_comments_are_kept() {
+        let name = "alice".to_owned();
+        assert_eq!(
+            surreal_quote!("
+                -- the notes of #name
+                SELECT * FROM note /* filtered  by #name */ WHERE name = #val(&name) // done
+            "),
+            "-- the notes of #name\nSELECT * FROM note /* filtered  by #name */ WHERE name = 'alice' // done\n"
+        );
+
+        // A line comment at the end of a block does not comment out what follows
+        let flag = true;
+        assert_eq!(
+            surreal_quote!("SELECT * FROM note #if(flag){ -- filtered\n} WHERE true"),
+            "SELECT * FROM note -- filtered\n WHERE true"
+        );
+    }
+
+    #[test]
+    fn test_escaped_hash() {
+        let name = "alice".to_owned();
+        assert_eq!(surreal_quote!("RETURN ##name + #val(&name)"), "RETURN #name + 'alice'");
+        assert_eq!(surreal_fragment!("RETURN ##name").statement, "RETURN #name");
+    }
+
+    #[test]
+    fn test_delimiters_inside_placeholder_strings() {
+        let SurrealFragment { statement: text, .. } = surreal_fragment!("RETURN #val(&\")\".to_owned())");
+        assert_eq!(text, "RETURN $p0");
+        assert_eq!(surreal_quote!("RETURN #val(&\")\".to_owned())"), "RETURN ')'");
+    }
+
+    #[tokio::test]
+    async fn test_literal_round_trip() {
+        let db = create_db().await;
+        let name = "alice".to_owned();
+        db.query(surreal_quote!("CREATE note:alice SET name = #val(&name), text = 'two  spaces, a #hash -- and /* no */ comment'"))
+            .await.unwrap().check().unwrap();
+
+        let notes: Vec<Note> = db.query(surreal_quote!("SELECT * FROM note -- every note\n")).await.unwrap().take(RPath::from(0)).unwrap();
+        assert_eq!(notes, vec![Note { name, text: "two  spaces, a #hash -- and /* no */ comment".to_owned() }]);
+    }
+}
+
+#[cfg(test)]
+mod test_quote_file {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, surreal_quote_file, SurrealDerive};
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Player {
+        #[surreal_field(id)]
+        name: String,
+        age: i64,
+    }
+
+    #[test]
+    fn test_quote_file() {
+        let min_age = 18;
+        let excluded = Some("bob".to_owned());
+        assert_eq!(
+            surreal_quote_file!("tests/queries/report.surql"),
+            "-- Players above the given age, the oldest first\nSELECT name, age FROM player WHERE age >= 18 AND name != 'bob' ORDER BY age DESC;"
+        );
+
+        let excluded: Option<String> = None;
+        assert_eq!(
+            surreal_quote_file!("tests/queries/report.surql"),
+            "-- Players above the given age, the oldest first\nSELECT name, age FROM player WHERE age >= 18 ORDER BY age DESC;"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_file() {
+        let db = create_db().await;
+        let players = vec![
+            Player { name: "alice".to_owned(), age: 30 },
+            Player { name: "bob".to_owned(), age: 40 },
+            Player { name: "carol".to_owned(), age: 12 },
+        ];
+        db.query(surreal_quote!("#insert(&players)")).await.unwrap().check().unwrap();
+
+        let min_age = 18;
+        let excluded = Some("alice".to_owned());
+        let found: Vec<Player> = db.query(surreal_quote_file!("tests/queries/report.surql")).await.unwrap().take(RPath::from(0)).unwrap();
+        assert_eq!(found, vec![players[1].clone()]);
+    }
+}
+
+#[cfg(test)]
+mod test_named_queries {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_queries, surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_fragment::SurrealFragment;
+    use surrealdb::sql::Value;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Player {
+        #[surreal_field(id)]
+        name: String,
+        age: i64,
+    }
+
+    surreal_queries!("tests/queries/players.surql");
+
+    mod bound {
+        use super::Player;
+        use surreal_derive_plus::surreal_queries;
+
+        surreal_queries!("tests/queries/players.surql", binding = params);
+    }
+
+    async fn players(db: &Surreal<Db>) -> Vec<Player> {
+        let players = vec![
+            Player { name: "alice".to_owned(), age: 30 },
+            Player { name: "bob".to_owned(), age: 40 },
+            Player { name: "carol".to_owned(), age: 12 },
+        ];
+        db.query(surreal_quote!("#insert(&players)")).await.unwrap().check().unwrap();
+        players
+    }
+
+    #[test]
+    fn test_statements() {
+        assert_eq!(count_players(), "SELECT count() AS total FROM player GROUP ALL;");
+        assert_eq!(adults(18, "bob".to_owned()), "SELECT * FROM player WHERE age >= 18 AND name != 'bob' ORDER BY age;");
+        assert_eq!(oldest(), "LET $players = SELECT * FROM player ORDER BY age DESC LIMIT 1; RETURN $players[0];");
+
+        let SurrealFragment { statement, params } = bound::adults(18, "bob".to_owned());
+        assert_eq!(statement, "SELECT * FROM player WHERE age >= $p0 AND name != $p1 ORDER BY age;");
+        assert_eq!(params["p0"], Value::from(18));
+        assert_eq!(params["p1"], Value::from("bob"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let db = create_db().await;
+        let players = players(&db).await;
+
+        assert_eq!(fetch_adults(&db, 18, "bob".to_owned()).await.unwrap(), vec![players[0].clone()]);
+        assert_eq!(bound::fetch_adults(&db, 10, "alice".to_owned()).await.unwrap(), vec![players[2].clone(), players[1].clone()]);
+        assert_eq!(fetch_oldest(&db).await.unwrap(), Some(players[1].clone()));
+    }
+}
+
+#[cfg(test)]
+mod test_captured_params {
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use surreal_derive_plus::{surreal_params, surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_fragment::SurrealFragment;
+    use surreal_devl::surreal_qr::RPath;
+    use surrealdb::sql::Value;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Player {
+        #[surreal_field(id)]
+        name: String,
+        age: i64,
+    }
+
+    #[test]
+    fn test_capture() {
+        let min_age = 18;
+        let name = "bob".to_owned();
+        let SurrealFragment { statement, params } = surreal_params!("SELECT * FROM player WHERE age >= $min_age AND name != $name AND age < #val(&65)");
+
+        assert_eq!(statement, "SELECT * FROM player WHERE age >= $min_age AND name != $name AND age < $p2");
+        assert_eq!(params, BTreeMap::from([
+            ("min_age".to_owned(), Value::from(18)),
+            ("name".to_owned(), Value::from("bob")),
+            ("p2".to_owned(), Value::from(65)),
+        ]));
+    }
+
+    // The generated names skip the captured ones and the ones written in the template
+    #[test]
+    fn test_generated_names_do_not_collide() {
+        let p1 = 5;
+        let x = 7;
+        let SurrealFragment { statement, params } = surreal_params!("a = $p1 AND b = #val(&x) AND c = #val(&x)");
+        assert_eq!(statement, "a = $p1 AND b = $p2 AND c = $p3");
+        assert_eq!(params, BTreeMap::from([
+            ("p1".to_owned(), Value::from(5)),
+            ("p2".to_owned(), Value::from(7)),
+            ("p3".to_owned(), Value::from(7)),
+        ]));
+
+        // Bound by the caller, eg: `.bind(("p0", ..))`
+        let fragment = surreal_derive_plus::surreal_fragment!("a = $p0 AND b = #val(&x)");
+        assert_eq!(fragment.statement, "a = $p0 AND b = $p1");
+        let SurrealFragment { statement, .. } = surreal_params!("a = $$p0 AND b = #val(&x)");
+        assert_eq!(statement, "a = $p0 AND b = $p1");
+
+        let inner = surreal_derive_plus::surreal_fragment!("c = #val(&x)");
+        let SurrealFragment { statement, params } = surreal_params!("a = $p1 AND #frag(inner) AND d = #val(&x)");
+        assert_eq!(statement, "a = $p1 AND c = $p2 AND d = $p3");
+        assert_eq!(params.keys().collect::<Vec<_>>(), vec!["p1", "p2", "p3"]);
+    }
+
+    #[test]
+    fn test_builtin_and_declared_params() {
+        let value = 1;
+        let this = 2;
+        let total = 3;
+        let SurrealFragment { statement, params } = surreal_params!("
+            LET $total = SELECT count() FROM player GROUP ALL;
+            UPDATE player SET score = $value + $this.age + $parent.age + $total WHERE $auth != NONE AND $session != NONE
+        ");
+        let _ = (value, this, total);
+
+        assert_eq!(statement, "LET $total = SELECT count() FROM player GROUP ALL; UPDATE player SET score = $value + $this.age + $parent.age + $total WHERE $auth != NONE AND $session != NONE");
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    fn test_defined_and_escaped_params() {
+        let param = 1;
+        let SurrealFragment { statement, params } = surreal_params!("
+            DEFINE PARAM $tax_rate VALUE 0.2;
+            DEFINE PARAM OVERWRITE $currency VALUE 'EUR';
+            DEFINE PARAM IF NOT EXISTS $region VALUE 'EU';
+            REMOVE PARAM IF EXISTS $legacy;
+            SELECT * FROM product WHERE param = $param AND tax = $tax_rate AND discount = $$discount
+        ");
+
+        assert_eq!(statement, "DEFINE PARAM $tax_rate VALUE 0.2; DEFINE PARAM OVERWRITE $currency VALUE 'EUR'; DEFINE PARAM IF NOT EXISTS $region VALUE 'EU'; REMOVE PARAM IF EXISTS $legacy; SELECT * FROM product WHERE param = $param AND tax = $tax_rate AND discount = $discount");
+        assert_eq!(params, BTreeMap::from([("param".to_owned(), Value::from(1))]));
+        assert_eq!(surreal_quote!("RETURN $$total"), "RETURN $total");
+    }
+
+    #[tokio::test]
+    async fn test_params_bound_later() {
+        let db = create_db().await;
+        let base = 10;
+        let SurrealFragment { statement, params } = surreal_params!("DEFINE PARAM OVERWRITE $tax_rate VALUE 2; RETURN ($base + $$bonus) * $tax_rate");
+
+        let total: Option<i64> = db.query(statement).bind(params).bind(("bonus", 5)).await.unwrap().take(1).unwrap();
+        assert_eq!(total, Some(30));
+    }
+
+    #[test]
+    fn test_literals_are_not_captured() {
+        let name = "bob".to_owned();
+        let SurrealFragment { statement, params } = surreal_params!("RETURN ['$name', $name] -- $name");
+
+        assert_eq!(statement, "RETURN ['$name', $name] -- $name");
+        assert_eq!(params, BTreeMap::from([("name".to_owned(), Value::from("bob"))]));
+    }
+
+    #[tokio::test]
+    async fn test_captured_query() {
+        let db = create_db().await;
+        let players = vec![
+            Player { name: "alice".to_owned(), age: 30 },
+            Player { name: "bob".to_owned(), age: 40 },
+            Player { name: "carol".to_owned(), age: 12 },
+        ];
+        db.query(surreal_quote!("#insert(&players)")).await.unwrap().check().unwrap();
+
+        let min_age = 18;
+        let excluded = &players[1].name;
+        let SurrealFragment { statement, params } = surreal_params!("SELECT * FROM player WHERE age >= $min_age AND name != $excluded");
+        let found: Vec<Player> = db.query(statement).bind(params).await.unwrap().take(RPath::from(0)).unwrap();
+        assert_eq!(found, vec![players[0].clone()]);
+
+        let SurrealFragment { statement, params } = queries::older(18);
+        assert_eq!(statement, "SELECT * FROM player WHERE age >= $min_age ORDER BY age;");
+        assert_eq!(params["min_age"], Value::from(18));
+        assert_eq!(queries::fetch_older(&db, 18).await.unwrap(), vec![players[0].clone(), players[1].clone()]);
+    }
+
+    mod queries {
+        use super::Player;
+        use surreal_derive_plus::surreal_queries;
+
+        surreal_queries!("tests/queries/captured.surql", binding = capture);
+    }
+}
+
+#[cfg(test)]
+mod test_typed_query {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_query_as, surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_qr::SurrealResponseError;
+    use surreal_devl::surreal_query::TypedQuery;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Player {
+        #[surreal_field(id)]
+        name: String,
+        age: i64,
+    }
+
+    async fn players(db: &Surreal<Db>) -> Vec<Player> {
+        let players = vec![
+            Player { name: "alice".to_owned(), age: 30 },
+            Player { name: "bob".to_owned(), age: 40 },
+        ];
+        db.query(surreal_quote!("#insert(&players)")).await.unwrap().check().unwrap();
+        players
+    }
+
+    #[tokio::test]
+    async fn test_execute() {
+        let db = create_db().await;
+        let players = players(&db).await;
+
+        let min_age = 35;
+        let query = surreal_query_as!(Vec<Player>, "SELECT * FROM #table(Player) WHERE age >= #min_age");
+        assert_eq!(query.to_string(), "SELECT * FROM player WHERE age >= 35");
+        assert_eq!(query.execute(&db).await.unwrap(), vec![players[1].clone()]);
+
+        let oldest = surreal_query_as!(Option<Player>, "SELECT * FROM player ORDER BY age DESC LIMIT 1").execute(&db).await.unwrap();
+        assert_eq!(oldest, Some(players[1].clone()));
+    }
+
+    #[tokio::test]
+    async fn test_statement_index() {
+        let db = create_db().await;
+        players(&db).await;
+
+        let total = surreal_query_as!(i64, "LET $players = SELECT * FROM player; RETURN count($players)")
+            .statement(1)
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+    }
+
+    #[tokio::test]
+    async fn test_error_context() {
+        let db = create_db().await;
+        players(&db).await;
+
+        let error = surreal_query_as!(Vec<i64>, "SELECT * FROM player").execute(&db).await.unwrap_err();
+        assert!(error.to_string().contains("statement 0 of `SELECT * FROM player`"), "{}", error);
+
+        // The error of the statement itself
+        let error = surreal_query_as!(i64, "LET $total = 1; THROW 'No player'").statement(1).execute(&db).await.unwrap_err();
+        let SurrealResponseError::StatementFailed(index, statement, error) = error else {
+            panic!("Expected the failed statement, got {:?}", error);
+        };
+        assert_eq!((index, statement.as_str()), (1, "LET $total = 1; THROW 'No player'"));
+        assert!(matches!(*error, SurrealResponseError::QueryFailed(ref message) if message.contains("No player")), "{:?}", error);
+    }
+
+    fn adults(min_age: i64) -> TypedQuery<Vec<Player>> {
+        surreal_query_as!(Vec<Player>, "SELECT * FROM player WHERE age >= #min_age ORDER BY age")
+    }
+
+    #[tokio::test]
+    async fn test_returned_query() {
+        let db = create_db().await;
+        let players = players(&db).await;
+
+        let queries: Vec<TypedQuery<Vec<Player>>> = vec![adults(18), adults(35)];
+        assert_eq!(queries[1].as_str(), "SELECT * FROM player WHERE age >= 35 ORDER BY age");
+        for (query, expected) in queries.into_iter().zip([players.clone(), vec![players[1].clone()]]) {
+            assert_eq!(query.execute(&db).await.unwrap(), expected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_transaction {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, surreal_transaction, SurrealDerive};
+    use surreal_devl::surreal_qr::{RPath, SurrealResponseError};
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Employee {
+        #[surreal_field(id)]
+        name: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Company {
+        #[surreal_field(id)]
+        name: String,
+        seats: i64,
+    }
+
+    #[tokio::test]
+    async fn test_transaction() {
+        let db = create_db().await;
+        let employee = Employee { name: "alice".to_owned() };
+        let company = Company { name: "devlog".to_owned(), seats: 1 };
+
+        let transaction = surreal_transaction! {
+            Option<Employee> = "CREATE ONLY #record(&employee)";
+            Option<Company> = "CREATE ONLY #record(&company)";
+            "LET $seats = (SELECT VALUE seats FROM ONLY #id(&company))";
+            assert "$seats > 0", "No seat left";
+            Vec<i64> = "RELATE #id(&employee)->works_at->#id(&company) RETURN VALUE 1";
+        };
+        assert_eq!(
+            transaction.to_string(),
+            "BEGIN TRANSACTION; CREATE ONLY employee:alice CONTENT { name: 'alice' }; CREATE ONLY company:devlog CONTENT { name: 'devlog', seats: 1 }; \
+            LET $seats = (SELECT VALUE seats FROM ONLY company:devlog); IF !($seats > 0) { THROW 'No seat left' }; \
+            RELATE employee:alice->works_at->company:devlog RETURN VALUE 1; COMMIT TRANSACTION;"
+        );
+
+        let (created_employee, created_company, relations) = transaction.execute(&db).await.unwrap();
+        assert_eq!(created_employee, Some(employee));
+        assert_eq!(created_company, Some(company));
+        assert_eq!(relations, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_entries_with_several_statements() {
+        let db = create_db().await;
+        let employees = vec![
+            Employee { name: "alice".to_owned() },
+            Employee { name: "bob".to_owned() },
+            Employee { name: "carol".to_owned() },
+        ];
+        let company = Company { name: "devlog".to_owned(), seats: 3 };
+
+        let (inserted, total, created_company, seats) = surreal_transaction! {
+            Vec<Employee> = "#insert(&employees, chunk = 2)";
+            i64 = "LET $employees = SELECT * FROM employee; count($employees)";
+            Option<Company> = "CREATE ONLY #record(&company)";
+            assert "$employees.len() <= 3", "Too many employees";
+            i64 = "SELECT VALUE seats FROM ONLY #id(&company)";
+        }.execute(&db).await.unwrap();
+
+        // The result of an entry is the one of its last statement
+        assert_eq!(inserted, vec![employees[2].clone()]);
+        assert_eq!(total, 3);
+        assert_eq!(created_company, Some(company));
+        assert_eq!(seats, 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_guard() {
+        let db = create_db().await;
+        let employee = Employee { name: "bob".to_owned() };
+        let company = Company { name: "devlog".to_owned(), seats: 0 };
+
+        let error = surreal_transaction! {
+            Option<Company> = "CREATE ONLY #record(&company)";
+            Option<Employee> = "CREATE ONLY #record(&employee)";
+            assert "(SELECT VALUE seats FROM ONLY #id(&company)) > 0", "No seat left";
+        }.execute(&db).await.unwrap_err();
+        assert!(error.to_string().contains("No seat left"), "{}", error);
+        let SurrealResponseError::StatementFailed(index, statement, _) = error else {
+            panic!("Expected the failed statement, got {:?}", error);
+        };
+        assert_eq!(index, 2);
+        assert!(statement.starts_with("BEGIN TRANSACTION;"), "{}", statement);
+
+        // Nothing was written
+        let employees: Vec<Employee> = db.query(surreal_quote!("SELECT * FROM employee")).await.unwrap().take(RPath::from(0)).unwrap();
+        assert!(employees.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_deserialization() {
+        let db = create_db().await;
+        let error = surreal_transaction! {
+            i64 = "RETURN 1";
+            Vec<Employee> = "RETURN 'devlog'";
+        }.execute(&db).await.unwrap_err();
+
+        let SurrealResponseError::StatementFailed(index, _, error) = error else {
+            panic!("Expected the failed statement, got {:?}", error);
+        };
+        assert_eq!(index, 1);
+        assert!(!matches!(*error, SurrealResponseError::QueryFailed(_)), "{:?}", error);
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod test_tracing {
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+    use surreal_derive_plus::{surreal_fragment, surreal_query_as, surreal_quote};
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    // Records the fields of every event and span, a span is `entered` while the query runs inside it
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<BTreeMap<String, String>>>>);
+
+    impl Recorder {
+        fn recorded(&self, kind: &str) -> Vec<BTreeMap<String, String>> {
+            self.0.lock().unwrap().iter().filter(|record| record["kind"] == kind).cloned().collect()
+        }
+    }
+
+    impl Visit for Recorder {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.lock().unwrap().last_mut().unwrap().insert(field.name().to_owned(), value.to_owned());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.lock().unwrap().last_mut().unwrap().insert(field.name().to_owned(), format!("{:?}", value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            // Only the records of the macros, not the ones of the database
+            !metadata.target().starts_with("surrealdb")
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut records = self.0.lock().unwrap();
+            records.push(BTreeMap::from([
+                ("kind".to_owned(), "span".to_owned()),
+                ("target".to_owned(), span.metadata().target().to_owned()),
+                ("name".to_owned(), span.metadata().name().to_owned()),
+            ]));
+            let id = Id::from_u64(records.len() as u64);
+            drop(records);
+
+            span.record(&mut self.clone());
+            id
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            self.0.lock().unwrap().push(BTreeMap::from([
+                ("kind".to_owned(), "event".to_owned()),
+                ("target".to_owned(), event.metadata().target().to_owned()),
+            ]));
+            event.record(&mut self.clone());
+        }
+
+        fn enter(&self, span: &Id) {
+            if let Some(record) = self.0.lock().unwrap().get_mut(span.into_u64() as usize - 1) {
+                record.insert("entered".to_owned(), "true".to_owned());
+            }
+        }
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_statement_event() {
+        let recorder = Recorder::default();
+        let password = "secret".to_owned();
+        let age = 18;
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let _ = surreal_quote!(log = true, "SELECT * FROM user WHERE password = #val(&password)");
+            let _ = surreal_fragment!(log = true, "SELECT * FROM user WHERE age > #age AND password = #val(&password)");
+        });
+
+        let events = recorder.recorded("event");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["target"], "surreal-ql");
+        assert_eq!(events[0]["file"], file!());
+        assert_eq!(events[0]["template"], "SELECT * FROM user WHERE password = #val(&password)");
+        assert_eq!(events[0]["length"], "SELECT * FROM user WHERE password = 'secret'".len().to_string());
+        assert_eq!(events[0]["params"], "[]");
+        assert_eq!(events[1]["params"], "[\"p0\", \"p1\"]");
+        assert_ne!(events[0]["template_id"], events[1]["template_id"]);
+        assert!(events.iter().all(|event| !event.values().any(|value| value.contains("secret"))));
+    }
+
+    #[test]
+    fn test_disabled_log_is_silent() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            // `surreal_enable_log` is off for the tests
+            let _ = surreal_quote!("SELECT * FROM user");
+            let _ = surreal_query_as!(Vec<i64>, "SELECT * FROM user");
+        });
+
+        assert!(recorder.0.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execution_span() {
+        let db = create_db().await;
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+        let query = surreal_query_as!(log = true, Vec<i64>, "RETURN [1, 2]");
+        assert!(recorder.recorded("span").iter().all(|span| !span.contains_key("entered")));
+
+        assert_eq!(query.execute(&db).await.unwrap(), vec![1, 2]);
+        let spans = recorder.recorded("span");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0]["name"], "surreal query");
+        assert_eq!(spans[0]["target"], "surreal-ql");
+        assert_eq!(spans[0]["file"], file!());
+        assert_eq!(spans[0]["query"], "RETURN [1, 2]");
+        assert_eq!(spans[0]["entered"], "true");
+    }
+
+    // Next to the same calls with `log = true`, only those are recorded
+    #[tokio::test]
+    async fn test_log_override() {
+        let db = create_db().await;
+        let recorder = Recorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+        let _ = surreal_quote!(log = false, "SELECT * FROM user WHERE age > 1");
+        let _ = surreal_quote!(log = true, namespace = "billing", "SELECT * FROM user WHERE age > 2");
+        surreal_query_as!(log = false, Vec<i64>, "RETURN [3]").execute(&db).await.unwrap();
+        surreal_query_as!(log = true, namespace = "billing", Vec<i64>, "RETURN [4]").execute(&db).await.unwrap();
+
+        let events = recorder.recorded("event");
+        assert_eq!(events.iter().map(|event| event["template"].as_str()).collect::<Vec<_>>(), vec!["SELECT * FROM user WHERE age > 2", "RETURN [4]"]);
+        assert!(events.iter().all(|event| event["target"] == "billing"));
+
+        let spans = recorder.recorded("span");
+        assert_eq!(spans.iter().map(|span| span["query"].as_str()).collect::<Vec<_>>(), vec!["RETURN [4]"]);
+        assert_eq!(spans[0]["target"], "billing");
+    }
+}
+
+#[cfg(test)]
+mod test_sensitive_fields {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+    use surreal_devl::surreal_qr::RPath;
+    use surreal_devl::surreal_sensitive::SurrealRedact;
+    use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+    async fn create_db() -> Surreal<Db> {
+        let db = Surreal::new::<Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Credentials {
+        login: String,
+        #[surreal_field(sensitive)]
+        token: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Account {
+        #[surreal_field(id)]
+        name: String,
+        #[surreal_field(sensitive)]
+        password: String,
+        credentials: Credentials,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    struct Vault {
+        #[surreal_field(id)]
+        name: String,
+        backup: Option<Credentials>,
+        missing: Option<Credentials>,
+        keys: Vec<Credentials>,
+        primary: Box<Credentials>,
+    }
+
+    fn credentials(login: &str) -> Credentials {
+        Credentials { login: login.to_owned(), token: "SECRET".to_owned() }
+    }
+
+    fn account() -> Account {
+        Account {
+            name: "alice".to_owned(),
+            password: "hash-123".to_owned(),
+            credentials: Credentials { login: "alice@devlog".to_owned(), token: "tk-456".to_owned() },
+        }
+    }
+
+    #[test]
+    fn test_redacted() {
+        assert_eq!(
+            account().surreal_redacted().to_string(),
+            "{ credentials: { login: 'alice@devlog', token: '***' }, name: 'alice', password: '***' }"
+        );
+    }
+
+    #[test]
+    fn test_containers_are_redacted() {
+        let vault = Vault {
+            name: "v1".to_owned(),
+            backup: Some(credentials("backup")),
+            missing: None,
+            keys: vec![credentials("k1"), credentials("k2")],
+            primary: Box::new(credentials("primary")),
+        };
+
+        assert_eq!(
+            vault.surreal_redacted().to_string(),
+            "{ backup: { login: 'backup', token: '***' }, keys: [{ login: 'k1', token: '***' }, { login: 'k2', token: '***' }], \
+             missing: NONE, name: 'v1', primary: { login: 'primary', token: '***' } }"
+        );
+        assert_eq!(Some(credentials("c")).surreal_redacted().to_string(), "{ login: 'c', token: '***' }");
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_fields_are_stored() {
+        let db = create_db().await;
+        let account = account();
+        db.query(surreal_quote!("CREATE #record(&account)")).await.unwrap().check().unwrap();
+
+        let stored: Option<Account> = db.query(surreal_quote!("SELECT * FROM ONLY #id(&account)")).await.unwrap().take(RPath::from(0)).unwrap();
+        assert_eq!(stored, Some(account));
+    }
+
+    // The `tracing` event never contains the statement
+    #[cfg(not(feature = "tracing"))]
+    mod logged_statement {
+        use std::cell::RefCell;
+
+        use serde_derive::{Deserialize, Serialize};
+        use surreal_derive_plus::{surreal_quote, SurrealDerive};
+        use surreal_devl::surreal_edge::Edge;
+        use surreal_devl::surreal_id::Link;
+
+        use super::{account, credentials, Vault};
+
+        thread_local! {
+            static LOGS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+        }
+
+        macro_rules! record_log {
+            ($($arg:tt)*) => {
+                LOGS.with(|logs| logs.borrow_mut().push(format!($($arg)*)))
+            };
+        }
+
+        fn logs() -> Vec<String> {
+            LOGS.with(|logs| logs.borrow_mut().drain(..).collect())
+        }
+
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+        struct Card {
+            #[surreal_field(id)]
+            number: String,
+            #[surreal_field(sensitive)]
+            pin: i64,
+            retries: i64,
+        }
+
+        #[test]
+        fn test_only_sensitive_fields_are_masked() {
+            let card = Card { number: "c1".to_owned(), pin: 1, retries: 10 };
+            let statement = surreal_quote!(log = true, info_log_macro = "record_log", "CREATE #record(&card); SELECT * FROM card LIMIT 1");
+
+            assert_eq!(statement, "CREATE card:c1 CONTENT { number: 'c1', pin: 1, retries: 10 }; SELECT * FROM card LIMIT 1");
+            assert_eq!(logs(), vec!["surreal-ql: CREATE card:c1 CONTENT { number: 'c1', pin: '***', retries: 10 }; SELECT * FROM card LIMIT 1".to_owned()]);
+        }
+
+        #[test]
+        fn test_every_placeholder_is_masked() {
+            let cards = vec![Card { number: "c1".to_owned(), pin: 1, retries: 10 }, Card { number: "c2".to_owned(), pin: 2, retries: 1 }];
+            let card = &cards[0];
+            let account = account();
+            let _ = surreal_quote!(log = true, info_log_macro = "record_log", "#insert(&cards)");
+            let _ = surreal_quote!(log = true, info_log_macro = "record_log", "UPDATE #id(card) #set(card) WHERE retries < #card.retries");
+            let _ = surreal_quote!(log = true, info_log_macro = "record_log", "UPDATE #id(&account) #merge(&account)");
+            let _ = surreal_quote!(log = true, info_log_macro = "record_log", "#upsert(&account); RETURN #val(&account.credentials)");
+
+            assert_eq!(logs(), vec![
+                "surreal-ql: INSERT INTO card [{ id: card:c1, number: 'c1', pin: '***', retries: 10 }, { id: card:c2, number: 'c2', pin: '***', retries: 1 }]".to_owned(),
+                "surreal-ql: UPDATE card:c1 SET number = 'c1', pin = '***', retries = 10 WHERE retries < 10".to_owned(),
+                "surreal-ql: UPDATE account:alice MERGE { credentials: { login: 'alice@devlog', token: '***' }, name: 'alice', password: '***' }".to_owned(),
+                "surreal-ql: UPSERT account:alice CONTENT { credentials: { login: 'alice@devlog', token: '***' }, name: 'alice', password: '***' }; RETURN { login: 'alice@devlog', token: '***' }".to_owned(),
+            ]);
+        }
+
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+        struct Grant {
+            #[surreal_field(id)]
+            scope: String,
+            #[surreal_field(sensitive)]
+            secret: String,
+        }
+
+        #[test]
+        fn test_containers_and_edges_are_masked() {
+            let vault = Vault {
+                name: "v1".to_owned(),
+                backup: Some(credentials("backup")),
+                missing: None,
+                keys: vec![credentials("k1")],
+                primary: Box::new(credentials("primary")),
+            };
+            let edge = Edge {
+                r#in: Some(Link::Record(account())),
+                out: Some(Link::Record(account())),
+                data: Grant { scope: "admin".to_owned(), secret: "SECRET".to_owned() },
+            };
+            let statement = surreal_quote!(log = true, info_log_macro = "record_log", "#relate(&edge)");
+            let _ = surreal_quote!(log = true, info_log_macro = "record_log", "CREATE #record(&vault)");
+
+            assert_eq!(statement, "RELATE account:alice -> grant:admin -> account:alice CONTENT { scope: 'admin', secret: 'SECRET' }");
+            let logs = logs();
+            assert_eq!(logs[0], "surreal-ql: RELATE account:alice -> grant:admin -> account:alice CONTENT { scope: 'admin', secret: '***' }");
+            assert!(!logs[1].contains("SECRET"), "{}", logs[1]);
+            assert!(logs[1].contains("keys: [{ login: 'k1', token: '***' }]"), "{}", logs[1]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_compile_dump {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::{surreal_quote, SurrealDerive};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(table = "dumped")]
+    struct DumpedRecord {
+        #[surreal_field(id)]
+        name: String,
+        #[surreal_field(sensitive)]
+        secret: String,
+    }
+
+    fn dump_dir() -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("target/surreal-expand")
+    }
+
+    // The dumps of this file, whose name without the `<file>_` prefix matches, the most recent first.
+    // The dumps of a previous version of the file stay in the directory, they are older than the current ones
+    fn dumps(matches: impl Fn(&str) -> bool) -> Vec<String> {
+        let prefix = format!("{}_", file!().replace(['/', '\\'], "_"));
+        let mut dumps = std::fs::read_dir(dump_dir()).unwrap()
+            .map(|entry| entry.unwrap())
+            .filter(|entry| entry.file_name().to_string_lossy().strip_prefix(&prefix).is_some_and(&matches))
+            .map(|entry| (entry.metadata().unwrap().modified().unwrap(), std::fs::read_to_string(entry.path()).unwrap()))
+            .collect::<Vec<_>>();
+        dumps.sort_by(|(l, _), (r, _)| r.cmp(l));
+        dumps.into_iter().map(|(_, content)| content).collect()
+    }
+
+    // `<line>_<suffix>.rs`, eg: `12_User.rs` for a derive or `12_5.rs` for a macro call
+    fn located(name: &str, line: Option<u32>, suffix: impl Fn(&str) -> bool) -> bool {
+        let Some((found, rest)) = name.strip_suffix(".rs").and_then(|name| name.split_once('_')) else {
+            return false;
+        };
+
+        found.parse::<u32>().is_ok_and(|found| line.is_none_or(|line| line == found)) && suffix(rest)
+    }
+
+    // The generated code matches its snapshot, `SURREAL_UPDATE_SNAPSHOTS=1` rewrites the snapshot
+    #[test]
+    fn test_derive_snapshot() {
+        let dumped = dumps(|name| located(name, None, |rest| rest == "DumpedRecord")).remove(0);
+        let snapshot = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/DumpedRecord.rs");
+        if std::env::var("SURREAL_UPDATE_SNAPSHOTS").is_ok() {
+            std::fs::write(&snapshot, &dumped).unwrap();
+        }
+
+        assert_eq!(dumped, std::fs::read_to_string(&snapshot).unwrap());
+    }
+
+    #[test]
+    fn test_call_site_dump() {
+        let (statement, line) = (surreal_quote!("SELECT * FROM #table(DumpedRecord)"), line!());
+        assert_eq!(statement, "SELECT * FROM dumped");
+
+        let dumped = dumps(|name| located(name, Some(line), |column| column.parse::<u32>().is_ok())).remove(0);
+        assert!(dumped.contains("\"SELECT * FROM {}\""), "{}", dumped);
+        assert!(dumped.contains("surreal_devl::surreal_table::SurrealTable"), "{}", dumped);
+    }
+
+    // The types of the same name in other modules of the file are dumped into their own files
+    #[test]
+    fn test_derive_dumps_do_not_collide() {
+        let accounts = dumps(|name| located(name, None, |rest| rest == "Account"));
+        assert!(accounts.len() >= 3, "{}", accounts.len());
+        // Only one of them has a sensitive field
+        assert!(accounts.iter().any(|dumped| dumped.contains("Value::from(\"***\")")));
+        assert!(accounts.iter().any(|dumped| !dumped.contains("Value::from(\"***\")")));
+    }
+}
+
+#[cfg(test)]
+mod test_config_overrides {
+    use serde_derive::{Deserialize, Serialize};
+    use surreal_derive_plus::SurrealDerive;
+    use surreal_devl::surreal_table::SurrealTable;
+    use surreal_devl::proxy::default::SurrealSerializer;
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SurrealDerive)]
+    #[surreal_derive(camel_case = true)]
+    struct Invoice {
+        #[surreal_field(id)]
+        invoice_number: String,
+        total_amount: i64,
+        #[surreal_field(sensitive)]
+        card_number: String,
+    }
+
+    fn invoice() -> Invoice {
+        Invoice { invoice_number: "inv-1".to_owned(), total_amount: 120, card_number: "4242".to_owned() }
+    }
+
+    #[test]
+    fn test_camel_case_attribute() {
+        assert_eq!(Invoice::FIELDS, &["invoiceNumber", "totalAmount", "cardNumber"]);
+        assert_eq!(invoice().serialize().to_string(), "{ cardNumber: '4242', invoiceNumber: 'inv-1', totalAmount: 120 }");
+    }
+
+    // The text log is replaced by the events of the `tracing` feature
+    #[cfg(not(feature = "tracing"))]
+    mod text_log {
+        use std::cell::RefCell;
+
+        use surreal_derive_plus::{surreal_queries, surreal_query_as, surreal_quote, surreal_transaction};
+        use surrealdb::{engine::local::{Db, Mem}, Surreal};
+
+        use super::{invoice, Invoice};
+
+        async fn create_db() -> Surreal<Db> {
+            let db = Surreal::new::<Mem>(()).await.unwrap();
+            db.use_ns("test").use_db("test").await.unwrap();
+            db
+        }
+
+        thread_local! {
+            static LOGS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+        }
+
+        macro_rules! record_log {
+            ($($arg:tt)*) => {
+                LOGS.with(|logs| logs.borrow_mut().push(format!($($arg)*)))
+            };
+        }
+
+        fn logs() -> Vec<String> {
+            LOGS.with(|logs| logs.borrow_mut().drain(..).collect())
+        }
+
+        surreal_queries!(log = true, namespace = "billing", info_log_macro = "record_log", "tests/queries/invoices.surql");
+
+        #[test]
+        fn test_log_override() {
+            let invoice = invoice();
+            let statement = surreal_quote!(log = true, namespace = "billing", info_log_macro = "record_log", "CREATE #record(&invoice)");
+
+            assert_eq!(statement, "CREATE invoice:`inv-1` CONTENT { cardNumber: '4242', invoiceNumber: 'inv-1', totalAmount: 120 }");
+            assert_eq!(logs(), vec!["billing: CREATE invoice:`inv-1` CONTENT { cardNumber: '***', invoiceNumber: 'inv-1', totalAmount: 120 }".to_owned()]);
+        }
+
+        #[test]
+        fn test_log_disabled() {
+            let statement = surreal_quote!(log = false, info_log_macro = "record_log", "SELECT * FROM #table(Invoice)");
+
+            assert_eq!(statement, "SELECT * FROM invoice");
+            assert!(logs().is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_typed_query_override() {
+            let db = create_db().await;
+            let invoice = invoice();
+            db.query(surreal_quote!("CREATE #record(&invoice)")).await.unwrap().check().unwrap();
+
+            let stored = surreal_query_as!(log = true, info_log_macro = "record_log", Option<Invoice>, "SELECT * FROM ONLY #id(&invoice)")
+                .execute(&db)
+                .await
+                .unwrap();
+            assert_eq!(stored, Some(invoice));
+            assert_eq!(logs(), vec!["surreal-ql: SELECT * FROM ONLY invoice:`inv-1`".to_owned()]);
+        }
+
+        #[tokio::test]
+        async fn test_transaction_override() {
+            let db = create_db().await;
+            let invoice = invoice();
+            let (created, total) = surreal_transaction! {
+                log = true, namespace = "billing", info_log_macro = "record_log",
+                Option<Invoice> = "CREATE ONLY #record(&invoice)";
+                i64 = "SELECT VALUE totalAmount FROM ONLY #id(&invoice)";
+            }.execute(&db).await.unwrap();
+
+            assert_eq!((created, total), (Some(invoice), 120));
+            assert_eq!(logs(), vec![
+                "billing: CREATE ONLY invoice:`inv-1` CONTENT { cardNumber: '***', invoiceNumber: 'inv-1', totalAmount: 120 }".to_owned(),
+                "billing: SELECT VALUE totalAmount FROM ONLY invoice:`inv-1`".to_owned(),
+            ]);
+        }
+
+        #[tokio::test]
+        async fn test_queries_override() {
+            let db = create_db().await;
+            let invoice = invoice();
+            db.query(surreal_quote!("CREATE #record(&invoice)")).await.unwrap().check().unwrap();
+
+            assert_eq!(invoices(), "SELECT * FROM invoice;");
+            assert_eq!(fetch_invoices(&db).await.unwrap(), vec![invoice]);
+            assert_eq!(logs(), vec!["billing: SELECT * FROM invoice;".to_owned(); 2]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_config_sources {
+    // The compiler records the inputs of the settings, a change of them rebuilds the crate
+    #[test]
+    fn test_settings_are_tracked() {
+        let dep_info = std::fs::read_to_string(std::env::current_exe().unwrap().with_extension("d")).unwrap();
+
+        for variable in ["SURREAL_DERIVE_ENABLE_LOG", "SURREAL_DERIVE_NAMESPACE", "SURREAL_DERIVE_USE_CAMEL_CASE", "SURREAL_DERIVE_COMPILE_DUMP_DIR"] {
+            assert!(dep_info.contains(&format!("# env-dep:{}", variable)), "{} is not tracked", variable);
+        }
+        assert!(dep_info.contains(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")));
+    }
+}
@@ -0,0 +1,522 @@
+const _: () = {
+    impl TryFrom<&surrealdb::sql::Object> for DumpedRecord {
+        type Error = surreal_devl::surreal_qr::SurrealResponseError;
+        fn try_from(
+            mut value_object: &surrealdb::sql::Object,
+        ) -> Result<Self, Self::Error> {
+            fn surreal_field<'a>(
+                object: &'a surrealdb::sql::Object,
+                name: &str,
+            ) -> Option<&'a surrealdb::sql::Value> {
+                if let Some(value) = object.get(name) {
+                    return Some(value);
+                }
+                let mut path = name.split('.');
+                let mut value = object.get(path.next()?)?;
+                for key in path {
+                    value = match value {
+                        surrealdb::sql::Value::Object(object) => object.get(key)?,
+                        _ => return None,
+                    };
+                }
+                Some(value)
+            }
+            return Ok(Self {
+                name: <String as surreal_devl::proxy::default::SurrealDeserializer>::from_option(
+                        surreal_field(value_object, "name"),
+                    )
+                    .map_err(|it| surreal_devl::surreal_qr::SurrealResponseError::ParsingFieldFailed(
+                        "name".to_string(),
+                        Box::new(it),
+                    ))?,
+                secret: <String as surreal_devl::proxy::default::SurrealDeserializer>::from_option(
+                        surreal_field(value_object, "secret"),
+                    )
+                    .map_err(|it| surreal_devl::surreal_qr::SurrealResponseError::ParsingFieldFailed(
+                        "secret".to_string(),
+                        Box::new(it),
+                    ))?,
+            });
+        }
+    }
+    impl From<DumpedRecord> for surrealdb::sql::Object {
+        fn from(mut value: DumpedRecord) -> Self {
+            let mut map: std::collections::BTreeMap<String, surrealdb::sql::Value> = std::collections::BTreeMap::new();
+            map.insert(
+                "name".to_owned(),
+                <String as surreal_devl::proxy::default::SurrealSerializer>::serialize(
+                    value.name.clone(),
+                ),
+            );
+            map.insert(
+                "secret".to_owned(),
+                <String as surreal_devl::proxy::default::SurrealSerializer>::serialize(
+                    value.secret.clone(),
+                ),
+            );
+            return Self::from(map);
+        }
+    }
+    impl DumpedRecord {
+        /// `DEFINE FIELD` and `DEFINE INDEX` statements describing this type inside `table`.
+        pub fn surreal_schema(table: &str) -> Vec<String> {
+            const SCHEMA_FIELDS: &[surreal_devl::surreal_schema::SchemaField] = &[
+                ("name", "string"),
+                ("secret", "string"),
+            ];
+            const SCHEMA_INDEXES: &[surreal_devl::surreal_schema::SchemaIndex] = &[];
+            surreal_devl::surreal_schema::schema(table, SCHEMA_FIELDS, SCHEMA_INDEXES)
+        }
+        /// Compares the result of `INFO FOR TABLE <table>` with the schema of this type,
+        /// returns the ordered statements to apply, each flagged `true` when it is destructive.
+        pub fn surreal_migration(
+            table: &str,
+            info: &surrealdb::sql::Value,
+        ) -> Vec<(String, bool)> {
+            const SCHEMA_FIELDS: &[surreal_devl::surreal_schema::SchemaField] = &[
+                ("name", "string"),
+                ("secret", "string"),
+            ];
+            const SCHEMA_INDEXES: &[surreal_devl::surreal_schema::SchemaIndex] = &[];
+            surreal_devl::surreal_schema::migration(
+                table,
+                SCHEMA_FIELDS,
+                SCHEMA_INDEXES,
+                info,
+            )
+        }
+    }
+    impl surreal_devl::surreal_id::SurrealId for DumpedRecord {
+        fn id(&self) -> surrealdb::sql::Thing {
+            let into_id = |value: surrealdb::sql::Value| match value {
+                surrealdb::sql::Value::Strand(id) => surrealdb::sql::Id::String(id.0),
+                surrealdb::sql::Value::Number(id) => {
+                    surrealdb::sql::Id::Number(id.as_int())
+                }
+                surrealdb::sql::Value::Uuid(id) => surrealdb::sql::Id::Uuid(id),
+                surrealdb::sql::Value::Array(id) => surrealdb::sql::Id::Array(id),
+                surrealdb::sql::Value::Object(id) => surrealdb::sql::Id::Object(id),
+                id => surrealdb::sql::Id::String(id.to_raw_string()),
+            };
+            surrealdb::sql::Thing::from((
+                <Self as surreal_devl::surreal_table::SurrealTable>::TABLE,
+                into_id(
+                    <String as surreal_devl::proxy::default::SurrealSerializer>::serialize(
+                        self.name.clone(),
+                    ),
+                ),
+            ))
+        }
+    }
+    impl DumpedRecord {
+        /// Range of record ids inside the table of this type, eg: `table:['London', NONE]..=['London', time::now()]`
+        pub fn id_range<K>(range: impl std::ops::RangeBounds<K>) -> surrealdb::sql::Thing
+        where
+            K: surreal_devl::proxy::default::SurrealSerializer + Clone + 'static,
+        {
+            let into_id = |value: surrealdb::sql::Value| match value {
+                surrealdb::sql::Value::Strand(id) => surrealdb::sql::Id::String(id.0),
+                surrealdb::sql::Value::Number(id) => {
+                    surrealdb::sql::Id::Number(id.as_int())
+                }
+                surrealdb::sql::Value::Uuid(id) => surrealdb::sql::Id::Uuid(id),
+                surrealdb::sql::Value::Array(id) => surrealdb::sql::Id::Array(id),
+                surrealdb::sql::Value::Object(id) => surrealdb::sql::Id::Object(id),
+                id => surrealdb::sql::Id::String(id.to_raw_string()),
+            };
+            let bound = |bound: std::ops::Bound<&K>| {
+                bound.cloned().map(|key| into_id(surreal_devl::surreal_id::id_key(key)))
+            };
+            let range = surrealdb::sql::IdRange::try_from((
+                    bound(range.start_bound()),
+                    bound(range.end_bound()),
+                ))
+                .expect("A record id range can not be nested");
+            surrealdb::sql::Thing::from((
+                <Self as surreal_devl::surreal_table::SurrealTable>::TABLE,
+                surrealdb::sql::Id::Range(Box::new(range)),
+            ))
+        }
+    }
+    impl surreal_devl::surreal_table::SurrealTable for DumpedRecord {
+        type Select = DumpedRecordSelect;
+        const TABLE: &'static str = "dumped";
+        const FIELDS: &'static [&'static str] = &["name", "secret"];
+    }
+    ///Paths to the fields of [`DumpedRecord`], eg: `DumpedRecord::fields().address().city()` renders `address.city`.
+    #[allow(dead_code)]
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct DumpedRecordFields(surrealdb::sql::Idiom);
+    impl From<surrealdb::sql::Idiom> for DumpedRecordFields {
+        fn from(path: surrealdb::sql::Idiom) -> Self {
+            Self(path)
+        }
+    }
+    impl From<DumpedRecordFields> for surrealdb::sql::Idiom {
+        fn from(fields: DumpedRecordFields) -> Self {
+            fields.0
+        }
+    }
+    impl std::fmt::Display for DumpedRecordFields {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+    ///Path to a single field of [`DumpedRecord`], eg: `DumpedRecord::fields().age().gt(18)`.
+    #[allow(dead_code)]
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct DumpedRecordField(surrealdb::sql::Idiom);
+    impl From<surrealdb::sql::Idiom> for DumpedRecordField {
+        fn from(path: surrealdb::sql::Idiom) -> Self {
+            Self(path)
+        }
+    }
+    impl From<DumpedRecordField> for surrealdb::sql::Idiom {
+        fn from(fields: DumpedRecordField) -> Self {
+            fields.0
+        }
+    }
+    impl std::fmt::Display for DumpedRecordField {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+    #[allow(dead_code)]
+    impl DumpedRecordField {
+        ///Condition `field = value`.
+        fn eq<V>(&self, value: V) -> surrealdb::sql::Value
+        where
+            V: surreal_devl::proxy::default::SurrealSerializer,
+        {
+            surrealdb::sql::Value::Expression(
+                Box::new(
+                    surrealdb::sql::Expression::new(
+                        surrealdb::sql::Value::Idiom(self.0.clone()),
+                        surrealdb::sql::Operator::Equal,
+                        surreal_devl::proxy::default::SurrealSerializer::serialize(value),
+                    ),
+                ),
+            )
+        }
+        ///Condition `field != value`.
+        fn ne<V>(&self, value: V) -> surrealdb::sql::Value
+        where
+            V: surreal_devl::proxy::default::SurrealSerializer,
+        {
+            surrealdb::sql::Value::Expression(
+                Box::new(
+                    surrealdb::sql::Expression::new(
+                        surrealdb::sql::Value::Idiom(self.0.clone()),
+                        surrealdb::sql::Operator::NotEqual,
+                        surreal_devl::proxy::default::SurrealSerializer::serialize(value),
+                    ),
+                ),
+            )
+        }
+        ///Condition `field > value`.
+        fn gt<V>(&self, value: V) -> surrealdb::sql::Value
+        where
+            V: surreal_devl::proxy::default::SurrealSerializer,
+        {
+            surrealdb::sql::Value::Expression(
+                Box::new(
+                    surrealdb::sql::Expression::new(
+                        surrealdb::sql::Value::Idiom(self.0.clone()),
+                        surrealdb::sql::Operator::MoreThan,
+                        surreal_devl::proxy::default::SurrealSerializer::serialize(value),
+                    ),
+                ),
+            )
+        }
+        ///Condition `field >= value`.
+        fn gte<V>(&self, value: V) -> surrealdb::sql::Value
+        where
+            V: surreal_devl::proxy::default::SurrealSerializer,
+        {
+            surrealdb::sql::Value::Expression(
+                Box::new(
+                    surrealdb::sql::Expression::new(
+                        surrealdb::sql::Value::Idiom(self.0.clone()),
+                        surrealdb::sql::Operator::MoreThanOrEqual,
+                        surreal_devl::proxy::default::SurrealSerializer::serialize(value),
+                    ),
+                ),
+            )
+        }
+        ///Condition `field < value`.
+        fn lt<V>(&self, value: V) -> surrealdb::sql::Value
+        where
+            V: surreal_devl::proxy::default::SurrealSerializer,
+        {
+            surrealdb::sql::Value::Expression(
+                Box::new(
+                    surrealdb::sql::Expression::new(
+                        surrealdb::sql::Value::Idiom(self.0.clone()),
+                        surrealdb::sql::Operator::LessThan,
+                        surreal_devl::proxy::default::SurrealSerializer::serialize(value),
+                    ),
+                ),
+            )
+        }
+        ///Condition `field <= value`.
+        fn lte<V>(&self, value: V) -> surrealdb::sql::Value
+        where
+            V: surreal_devl::proxy::default::SurrealSerializer,
+        {
+            surrealdb::sql::Value::Expression(
+                Box::new(
+                    surrealdb::sql::Expression::new(
+                        surrealdb::sql::Value::Idiom(self.0.clone()),
+                        surrealdb::sql::Operator::LessThanOrEqual,
+                        surreal_devl::proxy::default::SurrealSerializer::serialize(value),
+                    ),
+                ),
+            )
+        }
+        ///Condition `field CONTAINS value`.
+        fn contains<V>(&self, value: V) -> surrealdb::sql::Value
+        where
+            V: surreal_devl::proxy::default::SurrealSerializer,
+        {
+            surrealdb::sql::Value::Expression(
+                Box::new(
+                    surrealdb::sql::Expression::new(
+                        surrealdb::sql::Value::Idiom(self.0.clone()),
+                        surrealdb::sql::Operator::Contain,
+                        surreal_devl::proxy::default::SurrealSerializer::serialize(value),
+                    ),
+                ),
+            )
+        }
+        ///Condition `field INSIDE value`.
+        fn inside<V>(&self, value: V) -> surrealdb::sql::Value
+        where
+            V: surreal_devl::proxy::default::SurrealSerializer,
+        {
+            surrealdb::sql::Value::Expression(
+                Box::new(
+                    surrealdb::sql::Expression::new(
+                        surrealdb::sql::Value::Idiom(self.0.clone()),
+                        surrealdb::sql::Operator::Inside,
+                        surreal_devl::proxy::default::SurrealSerializer::serialize(value),
+                    ),
+                ),
+            )
+        }
+    }
+    #[allow(dead_code)]
+    impl DumpedRecordFields {
+        fn name(&self) -> DumpedRecordField {
+            DumpedRecordField::from({
+                let mut path = self.0.to_vec();
+                path.extend("name".split('.').map(surrealdb::sql::Part::from));
+                surrealdb::sql::Idiom::from(path)
+            })
+        }
+        fn secret(&self) -> DumpedRecordField {
+            DumpedRecordField::from({
+                let mut path = self.0.to_vec();
+                path.extend("secret".split('.').map(surrealdb::sql::Part::from));
+                surrealdb::sql::Idiom::from(path)
+            })
+        }
+    }
+    impl surreal_devl::surreal_table::SurrealFields for DumpedRecord {
+        type Fields = DumpedRecordFields;
+    }
+    ///`SELECT` statement loading [`DumpedRecord`] records, built from `DumpedRecord::select()`.
+    #[allow(dead_code)]
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct DumpedRecordSelect {
+        conditions: Vec<surrealdb::sql::Value>,
+        order: Vec<(surrealdb::sql::Idiom, bool)>,
+        limit: Option<u64>,
+        start: Option<u64>,
+        fetch: Vec<surrealdb::sql::Idiom>,
+    }
+    #[allow(dead_code)]
+    impl DumpedRecordSelect {
+        /// Keep the records matching the condition, several filters are joined with `AND`.
+        fn filter(mut self, condition: surrealdb::sql::Value) -> Self {
+            self.conditions.push(condition);
+            self
+        }
+        /// Keep the records matching any of the conditions, eg: `(age < 18 OR age > 65)`.
+        fn filter_any(
+            mut self,
+            conditions: impl IntoIterator<Item = surrealdb::sql::Value>,
+        ) -> Self {
+            let condition = conditions
+                .into_iter()
+                .reduce(|l, r| {
+                    surrealdb::sql::Value::Expression(
+                        Box::new(
+                            surrealdb::sql::Expression::new(
+                                l,
+                                surrealdb::sql::Operator::Or,
+                                r,
+                            ),
+                        ),
+                    )
+                });
+            if let Some(condition) = condition {
+                self.conditions
+                    .push(
+                        surrealdb::sql::Value::Subquery(
+                            Box::new(surrealdb::sql::Subquery::Value(condition)),
+                        ),
+                    );
+            }
+            self
+        }
+        /// Sort by the field in ascending order.
+        fn order_by(mut self, field: impl Into<surrealdb::sql::Idiom>) -> Self {
+            self.order.push((field.into(), true));
+            self
+        }
+        /// Sort by the field in descending order.
+        fn order_by_desc(mut self, field: impl Into<surrealdb::sql::Idiom>) -> Self {
+            self.order.push((field.into(), false));
+            self
+        }
+        /// Return at most `limit` records.
+        fn limit(mut self, limit: u64) -> Self {
+            self.limit = Some(limit);
+            self
+        }
+        /// Skip the first `start` records.
+        fn start(mut self, start: u64) -> Self {
+            self.start = Some(start);
+            self
+        }
+        /// Replace the record ids of the field by the records themselves, `FETCH field`.
+        fn fetch_related(mut self, field: impl Into<surrealdb::sql::Idiom>) -> Self {
+            self.fetch.push(field.into());
+            self
+        }
+        /// Run the statement and deserialize the selected records.
+        async fn fetch<C>(
+            self,
+            db: &surrealdb::Surreal<C>,
+        ) -> Result<Vec<DumpedRecord>, surreal_devl::surreal_qr::SurrealResponseError>
+        where
+            C: surrealdb::Connection,
+        {
+            let records: surreal_devl::surreal_qr::SurrealQR = db
+                .query(self.to_string())
+                .await?
+                .take(surreal_devl::surreal_qr::RPath::from(0))?;
+            let mut result = vec![];
+            for record in records.array()?.iter().flat_map(|records| records.iter()) {
+                result
+                    .push(
+                        <DumpedRecord as surreal_devl::proxy::default::SurrealDeserializer>::deserialize(
+                            record,
+                        )?,
+                    );
+            }
+            Ok::<_, surreal_devl::surreal_qr::SurrealResponseError>(result)
+        }
+    }
+    impl std::fmt::Display for DumpedRecordSelect {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f, "SELECT * FROM {}", surrealdb::sql::Table::from(< DumpedRecord as
+                surreal_devl::surreal_table::SurrealTable > ::TABLE)
+            )?;
+            if !self.conditions.is_empty() {
+                let conditions = self
+                    .conditions
+                    .iter()
+                    .map(|condition| condition.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, " WHERE {}", conditions.join(" AND "))?;
+            }
+            if !self.order.is_empty() {
+                let order = self
+                    .order
+                    .iter()
+                    .map(|(field, asc)| {
+                        format!("{} {}", field, if * asc { "ASC" } else { "DESC" })
+                    })
+                    .collect::<Vec<_>>();
+                write!(f, " ORDER BY {}", order.join(", "))?;
+            }
+            if let Some(limit) = self.limit {
+                write!(f, " LIMIT {}", limit)?;
+            }
+            if let Some(start) = self.start {
+                write!(f, " START {}", start)?;
+            }
+            if !self.fetch.is_empty() {
+                let fetch = self
+                    .fetch
+                    .iter()
+                    .map(|field| field.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, " FETCH {}", fetch.join(", "))?;
+            }
+            Ok(())
+        }
+    }
+    impl surreal_devl::surreal_sensitive::SurrealRedact for DumpedRecord {
+        fn surreal_redacted(&self) -> surrealdb::sql::Value {
+            let mut map: std::collections::BTreeMap<String, surrealdb::sql::Value> = std::collections::BTreeMap::new();
+            map.insert(
+                "name".to_owned(),
+                {
+                    use surreal_devl::surreal_sensitive::{
+                        RedactSensitive as _, RedactSerialized as _,
+                    };
+                    (&surreal_devl::surreal_sensitive::Redacted(&self.name))
+                        .surreal_redacted()
+                },
+            );
+            map.insert("secret".to_owned(), surrealdb::sql::Value::from("***"));
+            surrealdb::sql::Value::Object(map.into())
+        }
+    }
+    impl surreal_devl::proxy::default::SurrealDeserializer for DumpedRecord {
+        fn deserialize(
+            value: &surrealdb::sql::Value,
+        ) -> Result<Self, surreal_devl::surreal_qr::SurrealResponseError> {
+            let object = match &value {
+                surrealdb::sql::Value::Object(ref value) => value,
+                surrealdb::sql::Value::Array(ref value) => {
+                    if value.len() != 1 {
+                        return Err(
+                            surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnArrayWith1ItemToDeserializeToObject(
+                                format!("{:?}", value),
+                            ),
+                        )
+                    } else if let Some(surrealdb::sql::Value::Object(ref obj)) = value
+                        .0
+                        .first()
+                    {
+                        obj
+                    } else {
+                        return Err(
+                            surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnObject(
+                                format!("{:?}", value),
+                            ),
+                        )
+                    }
+                }
+                _ => {
+                    return Err(
+                        surreal_devl::surreal_qr::SurrealResponseError::ExpectedAnObject(
+                            format!("{:?}", value),
+                        ),
+                    );
+                }
+            };
+            Self::try_from(object)
+        }
+    }
+    impl surreal_devl::proxy::default::SurrealSerializer for DumpedRecord {
+        fn serialize(self) -> surrealdb::sql::Value {
+            let obj: surrealdb::sql::Object = self.into();
+            surrealdb::sql::Value::Object(obj)
+        }
+    }
+};